@@ -40,6 +40,7 @@ pub fn parse_top_attrs(attrs: TokenStream) -> Attrs {
 pub fn parse_enum(e: syn::ItemEnum, attrs: Attrs) -> syn::Result<TokenStream2> {
     let crate_name: syn::Ident = syn::Ident::new(CRATE_NAME_STR, Span::call_site());
     let ident = e.ident.clone();
+    let ident_string = ident.to_string();
 
     let top_level_attr_props = attrs::parse(&e.attrs)?;
     let serde_tag = top_level_attr_props.tag.unwrap_or("kind".to_owned());
@@ -174,12 +175,14 @@ pub fn parse_enum(e: syn::ItemEnum, attrs: Attrs) -> syn::Result<TokenStream2> {
 
         impl ::#crate_name::api::ApiBody for #ident {
             fn api_body_info() -> ::#crate_name::api::ApiBodyInfo {
-                ::#crate_name::api::ApiBodyInfo {
-                    description: #top_level_docs.to_owned(),
-                    ty: ::#crate_name::api::ApiBodyType::OneOf{
-                        values:vec![ #(#ts_impl_variants),* ]
+                ::#crate_name::api::named_api_body_info::<Self>(#ident_string, || {
+                    ::#crate_name::api::ApiBodyInfo {
+                        description: #top_level_docs.to_owned(),
+                        ty: ::#crate_name::api::ApiBodyType::OneOf{
+                            values:vec![ #(#ts_impl_variants),* ]
+                        }
                     }
-                }
+                })
             }
         }
     })
@@ -188,6 +191,7 @@ pub fn parse_enum(e: syn::ItemEnum, attrs: Attrs) -> syn::Result<TokenStream2> {
 pub fn parse_struct(s: syn::ItemStruct, attrs: Attrs) -> syn::Result<TokenStream2> {
     let crate_name: syn::Ident = syn::Ident::new(CRATE_NAME_STR, Span::call_site());
     let ident = s.ident.clone();
+    let ident_string = ident.to_string();
 
     let top_level_attr_props = attrs::parse(&s.attrs)?;
     let top_level_docs = top_level_attr_props.docs;
@@ -200,10 +204,12 @@ pub fn parse_struct(s: syn::ItemStruct, attrs: Attrs) -> syn::Result<TokenStream
             quote!{
                 impl ::#crate_name::api::ApiBody for #ident {
                     fn api_body_info() -> ::#crate_name::api::ApiBodyInfo {
-                        let mut t = #field_toks;
-                        let d = #top_level_docs;
-                        if d.len() > 0 { t.description = d.to_owned() }
-                        t
+                        ::#crate_name::api::named_api_body_info::<Self>(#ident_string, || {
+                            let mut t = #field_toks;
+                            let d = #top_level_docs;
+                            if d.len() > 0 { t.description = d.to_owned() }
+                            t
+                        })
                     }
                 }
             }
@@ -216,12 +222,14 @@ pub fn parse_struct(s: syn::ItemStruct, attrs: Attrs) -> syn::Result<TokenStream
             quote!{
                 impl ::#crate_name::api::ApiBody for #ident {
                     fn api_body_info() -> ::#crate_name::api::ApiBodyInfo {
-                        ::#crate_name::api::ApiBodyInfo {
-                            description: #top_level_docs.to_owned(),
-                            ty: ::#crate_name::api::ApiBodyType::TupleOf {
-                                values: vec![ #( #types ),* ]
+                        ::#crate_name::api::named_api_body_info::<Self>(#ident_string, || {
+                            ::#crate_name::api::ApiBodyInfo {
+                                description: #top_level_docs.to_owned(),
+                                ty: ::#crate_name::api::ApiBodyType::TupleOf {
+                                    values: vec![ #( #types ),* ]
+                                }
                             }
-                        }
+                        })
                     }
                 }
             }
@@ -259,11 +267,13 @@ pub fn parse_struct(s: syn::ItemStruct, attrs: Attrs) -> syn::Result<TokenStream
                 }
                 impl ::#crate_name::api::ApiBody for #ident {
                     fn api_body_info() -> ::#crate_name::api::ApiBodyInfo {
-                        let s = <#ident as ::#crate_name::api::ApiBodyStruct>::api_body_struct_info();
-                        ::#crate_name::api::ApiBodyInfo {
-                            description: s.description,
-                            ty: ::#crate_name::api::ApiBodyType::Object { keys: s.struc }
-                        }
+                        ::#crate_name::api::named_api_body_info::<Self>(#ident_string, || {
+                            let s = <#ident as ::#crate_name::api::ApiBodyStruct>::api_body_struct_info();
+                            ::#crate_name::api::ApiBodyInfo {
+                                description: s.description,
+                                ty: ::#crate_name::api::ApiBodyType::Object { keys: s.struc }
+                            }
+                        })
                     }
                 }
             }
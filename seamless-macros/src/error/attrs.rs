@@ -4,6 +4,9 @@ use syn::{ spanned::Spanned };
 pub struct FinalApiErrorAttrs {
     pub external_message: Option<String>,
     pub code: u16,
+    pub problem_type: Option<String>,
+    pub error_code: Option<String>,
+    pub value: bool,
     pub delegate_to_child: bool
 }
 
@@ -13,8 +16,11 @@ pub struct ApiErrorAttrs {
     external_tok: Option<syn::Path>,
     internal_tok: Option<syn::Path>,
     inner_tok: Option<syn::Path>,
+    value_tok: Option<syn::Path>,
     external_message: Option<syn::LitStr>,
-    code: Option<syn::LitInt>
+    code: Option<syn::LitInt>,
+    problem_type: Option<syn::LitStr>,
+    error_code: Option<syn::LitStr>
 }
 
 impl ApiErrorAttrs {
@@ -27,6 +33,10 @@ impl ApiErrorAttrs {
         let parse_str = |s: Option<syn::LitStr>| {
             s.map(|s| s.value()).unwrap_or(String::from("Internal server error"))
         };
+        let problem_type_tok = self.problem_type.take();
+        let problem_type = problem_type_tok.as_ref().map(|t| t.value());
+        let error_code = self.error_code.take();
+        let value = self.value_tok.is_some();
 
         // if there is an inner attr, force delegation to child:
         if self.inner_tok.is_some() {
@@ -45,6 +55,9 @@ impl ApiErrorAttrs {
             Ok(FinalApiErrorAttrs {
                 external_message: Some(parse_str(self.external_message)),
                 code: code,
+                problem_type: problem_type.clone(),
+                error_code: error_code.map(|c| c.value()),
+                value,
                 delegate_to_child: false
             })
         }
@@ -53,14 +66,30 @@ impl ApiErrorAttrs {
             Ok(FinalApiErrorAttrs {
                 external_message: None,
                 code: code,
+                problem_type: problem_type.clone(),
+                error_code: error_code.map(|c| c.value()),
+                value,
                 delegate_to_child: false
             })
         }
-        // Not internal or external? Delegate to the child impl (enums) or error if we can't:
+        // Not internal or external? We can't set a problem type, value or error code when delegating:
+        else if let Some(problem_type_tok) = problem_type_tok {
+            Err(syn::Error::new_spanned(problem_type_tok, "'type' requires one of 'internal', 'external' or 'external = \"foo\"'"))
+        }
+        else if let Some(value_tok) = self.value_tok {
+            Err(syn::Error::new_spanned(value_tok, "'value' requires one of 'internal', 'external' or 'external = \"foo\"'"))
+        }
+        else if let Some(error_code) = error_code {
+            Err(syn::Error::new_spanned(error_code, "'error_code' requires one of 'internal', 'external' or 'external = \"foo\"'"))
+        }
+        // Otherwise, delegate to the child impl (enums) or error if we can't:
         else {
             Ok(FinalApiErrorAttrs {
                 external_message: None,
                 code: 0,
+                problem_type: None,
+                error_code: None,
+                value: false,
                 delegate_to_child: true
             })
         }
@@ -70,6 +99,14 @@ impl ApiErrorAttrs {
         if let Some(t) = &parent.inner_tok {
             return Err(syn::Error::new_spanned(t, "This is not allowed at the top level of an enum, only specific fields"))
         }
+        // Likewise for "value":
+        if let Some(t) = &parent.value_tok {
+            return Err(syn::Error::new_spanned(t, "This is not allowed at the top level of an enum, only specific variants or fields"))
+        }
+        // Error codes identify a single error, so they can't be shared by every variant:
+        if let Some(t) = &parent.error_code {
+            return Err(syn::Error::new_spanned(t, "This is not allowed at the top level of an enum, only specific variants"))
+        }
         // If self does not identify as external or internal, use parent props for these:
         if self.external_tok.is_none() && self.internal_tok.is_none() && self.external_message.is_none() {
             self.external_tok = parent.external_tok.clone();
@@ -80,6 +117,12 @@ impl ApiErrorAttrs {
         if self.code.is_none() {
             self.code = parent.code.clone();
         }
+        // Likewise for the problem type, unless we're delegating to a child (which has its own):
+        let delegates = self.inner_tok.is_some()
+            || (self.external_tok.is_none() && self.internal_tok.is_none() && self.external_message.is_none());
+        if self.problem_type.is_none() && !delegates {
+            self.problem_type = parent.problem_type.clone();
+        }
         self.finalise()
     }
     pub fn parse(attrs: &[syn::Attribute]) -> syn::Result<ApiErrorAttrs> {
@@ -88,8 +131,11 @@ impl ApiErrorAttrs {
         let mut internal_tok: Option<syn::Path> = None;
         let mut external_tok: Option<syn::Path> = None;
         let mut inner_tok: Option<syn::Path> = None;
+        let mut value_tok: Option<syn::Path> = None;
         let mut external_message: Option<syn::LitStr> = None;
         let mut code: Option<syn::LitInt> = None;
+        let mut problem_type: Option<syn::LitStr> = None;
+        let mut error_code: Option<syn::LitStr> = None;
 
         let lit_str = |lit: syn::Lit| {
             match lit {
@@ -125,7 +171,7 @@ impl ApiErrorAttrs {
                 };
 
                 match meta {
-                    // Handle eg #[api_error(internal, external)]
+                    // Handle eg #[api_error(internal, value)]
                     syn::Meta::Path(path) => {
                         if path.is_ident("internal") {
                             internal_tok = Some(path);
@@ -133,16 +179,22 @@ impl ApiErrorAttrs {
                             external_tok = Some(path);
                         } else if path.is_ident("inner") {
                             inner_tok = Some(path)
+                        } else if path.is_ident("value") {
+                            value_tok = Some(path)
                         } else {
                             return Err(syn::Error::new_spanned(path, "unrecognized attribute"))
                         }
                     },
-                    // Handle eg #[api_error(internal = "foo", external = "bar", code = 200)]
+                    // Handle eg #[api_error(external = "bar", code = 200, error_code = "foo.bar")]
                     syn::Meta::NameValue(name_value) => {
                         if name_value.path.is_ident("external") {
                             external_message = Some(lit_str(name_value.lit)?);
                         } else if name_value.path.is_ident("code") {
                             code = Some(lit_int(name_value.lit)?);
+                        } else if name_value.path.is_ident("type") {
+                            problem_type = Some(lit_str(name_value.lit)?);
+                        } else if name_value.path.is_ident("error_code") {
+                            error_code = Some(lit_str(name_value.lit)?);
                         } else {
                             return Err(syn::Error::new_spanned(name_value, "unrecognized attribute"))
                         }
@@ -154,11 +206,13 @@ impl ApiErrorAttrs {
 
         // A thing can't be marked "inner" and have any other internal/external/code props,
         // since we'll be ignoring them all anyway:
-        if inner_tok.is_some() &&
-            (external_tok.is_some() || external_message.is_some()
-            || internal_tok.is_some() || code.is_some()) {
-                return Err(syn::Error::new_spanned(external_tok.unwrap(),
-                "'inner' does not make sense alongside any other attributes"))
+        if let Some(inner) = &inner_tok {
+            if external_tok.is_some() || external_message.is_some()
+                || internal_tok.is_some() || code.is_some() || problem_type.is_some() || value_tok.is_some()
+                || error_code.is_some() {
+                    return Err(syn::Error::new_spanned(inner,
+                    "'inner' does not make sense alongside any other attributes"))
+            }
         }
 
         // A thing can't be "external" and "internal" at once:
@@ -178,10 +232,36 @@ impl ApiErrorAttrs {
             external_tok: external_tok,
             internal_tok: internal_tok,
             inner_tok: inner_tok,
+            value_tok,
             external_message: external_message,
-            code: code
+            code: code,
+            problem_type,
+            error_code
         })
 
     }
 }
 
+// Is the field with the attributes provided marked with `#[api_error(value)]`? This
+// is the only attribute that fields can be given.
+pub fn is_value_field(attrs: &[syn::Attribute]) -> syn::Result<bool> {
+    let mut is_value = false;
+    for attr in attrs {
+        if !attr.path.is_ident("api_error") {
+            continue
+        }
+        let meta_list = match attr.parse_meta()? {
+            syn::Meta::List(list) => list,
+            bad => return Err(syn::Error::new_spanned(bad, "unrecognized attribute"))
+        };
+        for item in meta_list.nested {
+            match item {
+                syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident("value") => {
+                    is_value = true;
+                },
+                bad => return Err(syn::Error::new_spanned(bad, "only 'value' is allowed on fields"))
+            }
+        }
+    }
+    Ok(is_value)
+}
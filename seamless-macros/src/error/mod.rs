@@ -2,7 +2,7 @@ mod attrs;
 
 use quote::{ quote, quote_spanned };
 use proc_macro2::{ TokenStream as TokenStream2, Span };
-use attrs::ApiErrorAttrs;
+use attrs::{ ApiErrorAttrs, FinalApiErrorAttrs, is_value_field };
 
 pub fn parse_struct(s: syn::ItemStruct) -> TokenStream2 {
 
@@ -21,17 +21,30 @@ pub fn parse_struct(s: syn::ItemStruct) -> TokenStream2 {
         Err(e) => return e.to_compile_error()
     };
 
+    // Which fields (if any) have been marked with #[api_error(value)]:
+    let value_fields = match value_fields(&s.fields, &attrs) {
+        Ok(fields) => fields,
+        Err(e) => return e.to_compile_error()
+    };
+
     // For structs with 1 unnamed field, we can delegate to the inner ApiError, else error:
     if attrs.delegate_to_child {
-        if let Err(e) = one_unnamed_field(&s.ident, &s.fields) {
-            return e.to_compile_error();
-        }
+        let inner_ty = match one_unnamed_field(&s.ident, &s.fields) {
+            Ok(ty) => ty,
+            Err(e) => return e.to_compile_error()
+        };
+        let inner_info = inner_error_info_tokens(&crate_name, inner_ty);
         return quote! {
             impl From<#struct_name> for #crate_name::api::ApiError {
                 fn from(s: #struct_name) -> #crate_name::api::ApiError {
                     s.0.into()
                 }
             }
+            impl #crate_name::api::ApiErrors for #struct_name {
+                fn api_error_info() -> Vec<#crate_name::api::ApiErrorInfo> {
+                    #inner_info
+                }
+            }
         }
     }
 
@@ -44,6 +57,18 @@ pub fn parse_struct(s: syn::ItemStruct) -> TokenStream2 {
     }
 
 
+    // The value to hand back with the error, if any, and a description of it:
+    let value = match error_value_tokens(&crate_name, &s.ident, &s.fields, &attrs, &value_fields, |idx, field| {
+        let member = field_member(idx, field);
+        quote!{ &s.#member }
+    }) {
+        Ok(value) => value,
+        Err(e) => return e.to_compile_error()
+    };
+    let value_tok = value.as_ref().map(|v| v.value.clone()).unwrap_or(quote!{ None });
+    let error_code_tok = optional_string_tokens(error_code(&attrs, struct_name, None));
+    let info_tok = error_info_tokens(&crate_name, &attrs, value.as_ref(), &error_code_tok);
+
     // What we'll set as the external message:
     let external_msg_tok = if let Some(msg) = attrs.external_message {
         quote!{ #msg.to_owned() }
@@ -52,6 +77,7 @@ pub fn parse_struct(s: syn::ItemStruct) -> TokenStream2 {
     };
 
     let code = syn::LitInt::new(&attrs.code.to_string(), Span::call_site());
+    let problem_type_tok = optional_string_tokens(attrs.problem_type);
 
     quote!{
         impl From<#struct_name> for #crate_name::api::ApiError {
@@ -60,10 +86,17 @@ pub fn parse_struct(s: syn::ItemStruct) -> TokenStream2 {
                     code: #code,
                     internal_message: format!("{}", s),
                     external_message: #external_msg_tok,
-                    value: None
+                    value: #value_tok,
+                    problem_type: #problem_type_tok,
+                    error_code: #error_code_tok
                 }
             }
         }
+        impl #crate_name::api::ApiErrors for #struct_name {
+            fn api_error_info() -> Vec<#crate_name::api::ApiErrorInfo> {
+                vec![#info_tok]
+            }
+        }
     }
 }
 
@@ -83,6 +116,7 @@ pub fn parse_enum(e: syn::ItemEnum) -> TokenStream2 {
     }
 
     let mut enum_items = TokenStream2::new();
+    let mut info_items = TokenStream2::new();
     for variant in e.variants {
 
         let inner_attrs = match ApiErrorAttrs::parse(&variant.attrs) {
@@ -97,19 +131,67 @@ pub fn parse_enum(e: syn::ItemEnum) -> TokenStream2 {
 
         let ident = &variant.ident;
 
+        // Which fields (if any) have been marked with #[api_error(value)]:
+        let value_fields = match value_fields(&variant.fields, &attrs) {
+            Ok(fields) => fields,
+            Err(e) => return e.to_compile_error()
+        };
+
         // rely on the inner implementation if attrs not provided and there is one to rely on:
         if attrs.delegate_to_child {
-            if let Err(e) = one_unnamed_field(&ident, &variant.fields) {
-                return e.to_compile_error()
-            }
+            let inner_ty = match one_unnamed_field(ident, &variant.fields) {
+                Ok(ty) => ty,
+                Err(e) => return e.to_compile_error()
+            };
+            let inner_info = inner_error_info_tokens(&crate_name, inner_ty);
             enum_items.extend(quote! {
                 #struct_name::#ident (inner) => inner.into(),
-            })
+            });
+            info_items.extend(quote! {
+                errors.extend(#inner_info);
+            });
+            continue
         }
 
-        let full_ident = match variant.fields {
-            syn::Fields::Named(..) => quote!{ #ident {..} },
-            syn::Fields::Unnamed(..) => quote!{ #ident (..) },
+        // The value to hand back with the error, if any, and a description of it. Fields
+        // used in the value are bound by reference in the match arm:
+        let value = match error_value_tokens(&crate_name, ident, &variant.fields, &attrs, &value_fields, |idx, _| {
+            let binding = value_binding(idx);
+            quote!{ #binding }
+        }) {
+            Ok(value) => value,
+            Err(e) => return e.to_compile_error()
+        };
+        let value_tok = value.as_ref().map(|v| v.value.clone()).unwrap_or(quote!{ None });
+        let error_code_tok = optional_string_tokens(error_code(&attrs, struct_name, Some(ident)));
+        let info_tok = error_info_tokens(&crate_name, &attrs, value.as_ref(), &error_code_tok);
+        info_items.extend(quote! {
+            errors.push(#info_tok);
+        });
+
+        let used_fields = value.map(|v| v.fields).unwrap_or_default();
+        let full_ident = match &variant.fields {
+            syn::Fields::Named(fields) => {
+                let bindings = fields.named.iter().enumerate()
+                    .filter(|(idx, _)| used_fields.contains(idx))
+                    .map(|(idx, field)| {
+                        let name = &field.ident;
+                        let binding = value_binding(idx);
+                        quote!{ #name: ref #binding, }
+                    });
+                quote!{ #ident { #(#bindings)* .. } }
+            },
+            syn::Fields::Unnamed(fields) => {
+                let bindings = (0..fields.unnamed.len()).map(|idx| {
+                    if used_fields.contains(&idx) {
+                        let binding = value_binding(idx);
+                        quote!{ ref #binding }
+                    } else {
+                        quote!{ _ }
+                    }
+                });
+                quote!{ #ident ( #(#bindings),* ) }
+            },
             syn::Fields::Unit => quote!{ #ident }
         };
         let code = syn::LitInt::new(&attrs.code.to_string(), Span::call_site());
@@ -118,13 +200,16 @@ pub fn parse_enum(e: syn::ItemEnum) -> TokenStream2 {
         } else {
             quote!{ format!("{}", s) }
         };
+        let problem_type_tok = optional_string_tokens(attrs.problem_type);
 
         enum_items.extend(quote! {
             #struct_name::#full_ident => #crate_name::api::ApiError {
                 code: #code,
                 internal_message: format!("{}", s),
                 external_message: #external_msg_tok,
-                value: None
+                value: #value_tok,
+                problem_type: #problem_type_tok,
+                error_code: #error_code_tok
             },
         })
 
@@ -138,10 +223,217 @@ pub fn parse_enum(e: syn::ItemEnum) -> TokenStream2 {
                 }
             }
         }
+        impl #crate_name::api::ApiErrors for #struct_name {
+            fn api_error_info() -> Vec<#crate_name::api::ApiErrorInfo> {
+                let mut errors = vec![];
+                #info_items
+                errors
+            }
+        }
+    }
+}
+
+// A description of the error that the attributes given lead to. If there's no fixed
+// external message, it'll be the `Display` output of the error, which we can't know.
+fn error_info_tokens(crate_name: &syn::Ident, attrs: &FinalApiErrorAttrs, value: Option<&ErrorValue>, error_code_tok: &TokenStream2) -> TokenStream2 {
+    let code = syn::LitInt::new(&attrs.code.to_string(), Span::call_site());
+    let external_msg_tok = match &attrs.external_message {
+        Some(msg) => quote!{ Some(#msg.to_owned()) },
+        None => quote!{ None }
+    };
+    let problem_type_tok = optional_string_tokens(attrs.problem_type.clone());
+    let value_info_tok = match value {
+        Some(value) => {
+            let info = &value.info;
+            quote!{ Some(#info) }
+        },
+        None => quote!{ None }
+    };
+    quote!{
+        #crate_name::api::ApiErrorInfo {
+            code: #code,
+            external_message: #external_msg_tok,
+            value: #value_info_tok,
+            problem_type: #problem_type_tok,
+            error_code: #error_code_tok
+        }
+    }
+}
+
+// The value handed back with an error, a description of its shape, and the indexes of the
+// fields that it's built from.
+struct ErrorValue {
+    value: TokenStream2,
+    info: TokenStream2,
+    fields: Vec<usize>
+}
+
+// Find the indexes of any fields marked with #[api_error(value)]. At most one field can be
+// marked, and only if the struct/variant isn't itself the value or delegating to its child.
+fn value_fields(fields: &syn::Fields, attrs: &FinalApiErrorAttrs) -> syn::Result<Vec<usize>> {
+    let mut marked = vec![];
+    for (idx, field) in fields.iter().enumerate() {
+        if !is_value_field(&field.attrs)? {
+            continue
+        }
+        if attrs.delegate_to_child {
+            return Err(syn::Error::new_spanned(field,
+                "'value' requires one of 'internal', 'external' or 'external = \"foo\"'"))
+        }
+        if attrs.value {
+            return Err(syn::Error::new_spanned(field,
+                "'value' has already been given for the whole struct or variant"))
+        }
+        if !marked.is_empty() {
+            return Err(syn::Error::new_spanned(field,
+                "'value' can only be given on one field"))
+        }
+        marked.push(idx);
+    }
+    Ok(marked)
+}
+
+// Build the value to hand back with an error (if any), given how to access each field.
+fn error_value_tokens<F>(crate_name: &syn::Ident, ident: &syn::Ident, fields: &syn::Fields, attrs: &FinalApiErrorAttrs, value_fields: &[usize], field_expr: F) -> syn::Result<Option<ErrorValue>>
+where F: Fn(usize, &syn::Field) -> TokenStream2
+{
+    // A single field is the value:
+    if let Some(&idx) = value_fields.first() {
+        let field = fields.iter().nth(idx).expect("field exists");
+        let expr = field_expr(idx, field);
+        let ty = &field.ty;
+        return Ok(Some(ErrorValue {
+            value: quote!{ #crate_name::api::error_value(#expr) },
+            info: quote!{ <#ty as #crate_name::api::ApiBody>::api_body_info() },
+            fields: vec![idx]
+        }))
+    }
+
+    // The whole struct or variant isn't the value:
+    if !attrs.value {
+        return Ok(None)
+    }
+
+    let all_fields: Vec<usize> = (0..fields.len()).collect();
+    match fields {
+        // Named fields are handed back as an object:
+        syn::Fields::Named(named) => {
+            let names: Vec<String> = named.named.iter()
+                .map(|f| f.ident.as_ref().expect("named field").to_string())
+                .collect();
+            let exprs = named.named.iter().enumerate().map(|(idx, field)| field_expr(idx, field));
+            let tys = named.named.iter().map(|field| &field.ty);
+            Ok(Some(ErrorValue {
+                value: quote!{
+                    #crate_name::api::error_value_object(vec![
+                        #( (#names, #crate_name::api::error_value(#exprs)), )*
+                    ])
+                },
+                info: quote!{
+                    #crate_name::api::error_value_object_info(vec![
+                        #( (#names, <#tys as #crate_name::api::ApiBody>::api_body_info()), )*
+                    ])
+                },
+                fields: all_fields
+            }))
+        },
+        // A single unnamed field is handed back as it is:
+        syn::Fields::Unnamed(unnamed) if unnamed.unnamed.len() == 1 => {
+            let field = &unnamed.unnamed[0];
+            let expr = field_expr(0, field);
+            let ty = &field.ty;
+            Ok(Some(ErrorValue {
+                value: quote!{ #crate_name::api::error_value(#expr) },
+                info: quote!{ <#ty as #crate_name::api::ApiBody>::api_body_info() },
+                fields: all_fields
+            }))
+        },
+        // Several unnamed fields are handed back as an array:
+        syn::Fields::Unnamed(unnamed) => {
+            let exprs = unnamed.unnamed.iter().enumerate().map(|(idx, field)| field_expr(idx, field));
+            let tys = unnamed.unnamed.iter().map(|field| &field.ty);
+            Ok(Some(ErrorValue {
+                value: quote!{ #crate_name::api::error_value(&( #(#exprs,)* )) },
+                info: quote!{ <( #(#tys,)* ) as #crate_name::api::ApiBody>::api_body_info() },
+                fields: all_fields
+            }))
+        },
+        syn::Fields::Unit => {
+            Err(syn::Error::new_spanned(ident, "'value' requires a struct or variant with fields"))
+        }
+    }
+}
+
+// How to access a field of a struct:
+fn field_member(idx: usize, field: &syn::Field) -> syn::Member {
+    match &field.ident {
+        Some(ident) => syn::Member::Named(ident.clone()),
+        None => syn::Member::Unnamed(syn::Index::from(idx))
+    }
+}
+
+// The name that a field of an enum variant is bound to in order to access it:
+fn value_binding(idx: usize) -> syn::Ident {
+    quote::format_ident!("__value_{}", idx)
+}
+
+// The error code given in the attributes, or else one derived from the name of the struct
+// (eg "out_of_credit" for `OutOfCreditError`) or enum and variant (eg "maths.divide_by_zero"
+// for `MathsError::DivideByZero`). Derived codes would give away which internal error
+// occurred, so internal errors only have an error code if one is given.
+fn error_code(attrs: &FinalApiErrorAttrs, type_ident: &syn::Ident, variant_ident: Option<&syn::Ident>) -> Option<String> {
+    if let Some(error_code) = &attrs.error_code {
+        return Some(error_code.clone())
+    }
+    if attrs.external_message.is_some() {
+        return None
+    }
+    let type_name = type_ident.to_string();
+    let type_name = match type_name.strip_suffix("Error") {
+        Some(name) if !name.is_empty() => name,
+        _ => &type_name
+    };
+    Some(match variant_ident {
+        Some(variant) => format!("{}.{}", snake_case(type_name), snake_case(&variant.to_string())),
+        None => snake_case(type_name)
+    })
+}
+
+// Convert eg "DivideByZero" or "HTTPFailure" into "divide_by_zero" or "http_failure":
+fn snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut out = String::new();
+    for (idx, &c) in chars.iter().enumerate() {
+        if c.is_uppercase() && idx > 0 {
+            let prev = chars[idx - 1];
+            let next_is_lower = chars.get(idx + 1).map(|n| n.is_lowercase()).unwrap_or(false);
+            if prev != '_' && (prev.is_lowercase() || prev.is_numeric() || (prev.is_uppercase() && next_is_lower)) {
+                out.push('_');
+            }
+        }
+        out.extend(c.to_lowercase());
+    }
+    out
+}
+
+fn optional_string_tokens(s: Option<String>) -> TokenStream2 {
+    match s {
+        Some(t) => quote!{ Some(#t.to_owned()) },
+        None => quote!{ None }
     }
 }
 
-fn one_unnamed_field(ident: &syn::Ident, fields: &syn::Fields) -> syn::Result<()> {
+// A description of the errors that an inner type we delegate to can be converted into.
+// Inner types only need to convert into an ApiError, so if they don't implement ApiErrors
+// as well, they're described as having no errors.
+fn inner_error_info_tokens(crate_name: &syn::Ident, inner_ty: &syn::Type) -> TokenStream2 {
+    quote! {{
+        use #crate_name::api::{ ViaApiErrors as _, ViaFallback as _ };
+        (&#crate_name::api::ApiErrorsProbe::<#inner_ty>(std::marker::PhantomData)).api_error_info()
+    }}
+}
+
+fn one_unnamed_field<'a>(ident: &syn::Ident, fields: &'a syn::Fields) -> syn::Result<&'a syn::Type> {
     let fields: Vec<_> = match fields {
         syn::Fields::Unnamed(fields) => fields.unnamed.iter().collect(),
         _ => return Err(syn::Error::new_spanned(ident,
@@ -153,5 +445,5 @@ fn one_unnamed_field(ident: &syn::Ident, fields: &syn::Fields) -> syn::Result<()
                    "One of '#[api_error(internal)]' or '#[api_error(external)]' or \
                    '#[api_error(external = \"foo\")]' is required (2)"))
     }
-    Ok(())
+    Ok(&fields[0].ty)
 }
\ No newline at end of file
@@ -29,6 +29,10 @@ use syn::{ spanned::Spanned };
 /// Unit and non-unit variants cannot exist in the same enum for this reason, so that it is
 /// "obvious" how the thing will be (de)serialized.
 ///
+/// Types using this macro are "named" after the Rust type. `Api::info_with_definitions()`
+/// describes each named type just once, and refers to it by name (via `ApiBodyType::Ref`)
+/// everywhere that it's used.
+///
 /// # Example
 ///
 /// ```
@@ -164,6 +168,11 @@ pub fn ApiBody(attrs: TokenStream, input: TokenStream) -> TokenStream {
 ///
 /// You can also set a status code, otherwise the error will return with a status code set to 500.
 ///
+/// External errors are also given a stable `error_code`, which by default is derived from the name of
+/// the type (and variant, for enums). This will not change if the error messages do, so API consumers
+/// can rely on it to tell errors apart. Internal errors only have an `error_code` if one is given
+/// (see below), so that which internal error occurred isn't given away.
+///
 /// # Attributes
 ///
 /// Several attributes can be provided to tweak how this works:
@@ -175,6 +184,20 @@ pub fn ApiBody(attrs: TokenStream, input: TokenStream) -> TokenStream {
 ///   the `internal_message` field only (similar to `internal`, above).
 /// - `#[api_error(code = 401)]`: At the top of a struct of enum variant, this
 ///   sets the status code to be returned in the `ApiError` struct.
+/// - `#[api_error(type = "https://example.com/problems/foo")]`: At the top of a struct or enum
+///   variant, this sets the `problem_type` of the `ApiError` struct, which identifies the type of
+///   problem when errors are handed back as RFC 7807 problem details.
+/// - `#[api_error(error_code = "maths.divide_by_zero")]`: At the top of a struct or on an enum
+///   variant, this sets the `error_code` of the `ApiError` struct, which API consumers can use to
+///   tell errors apart. Without this, the error code of an `external` error is derived from the name
+///   of the struct (eg `"out_of_credit"` for `OutOfCreditError`), or the names of the enum and variant
+///   (eg `"maths.divide_by_zero"` for `MathsError::DivideByZero`), and other errors have no error code.
+/// - `#[api_error(value)]`: On a field, this serializes the field into the `value` of the
+///   `ApiError` struct, so that extra details (a validation failure, or a number of seconds to wait
+///   before retrying for instance) can be handed back with the error. At the top of a struct or
+///   on an enum variant, all of the fields are handed back instead (as an object if they are named,
+///   or an array if there are several unnamed fields). The shape of the value is described in the
+///   route information, so the type of each field needs to implement `ApiBody` and `Serialize`.
 ///
 /// These attributes can be combined.
 ///
@@ -184,7 +207,7 @@ pub fn ApiBody(attrs: TokenStream, input: TokenStream) -> TokenStream {
 /// # use seamless::ApiError;
 /// # use std::fmt;
 /// #[derive(ApiError, Debug)]
-/// #[api_error(internal, code = 401, external = "Whoops!")]
+/// #[api_error(internal, code = 401, external = "Whoops!", error_code = "whoops")]
 /// struct MyError;
 ///
 /// // We could use something like `thiserror` to generate our `Display` impls:
@@ -201,9 +224,29 @@ pub fn ApiBody(attrs: TokenStream, input: TokenStream) -> TokenStream {
 ///         code: 401,
 ///         internal_message: "A thing has gone wrong".to_owned(),
 ///         external_message: "Whoops!".to_owned(),
-///         value: None
+///         value: None,
+///         problem_type: None,
+///         error_code: Some("whoops".to_owned())
 ///     }
 /// );
+///
+/// // We can hand back details alongside the error:
+/// #[derive(ApiError, Debug)]
+/// #[api_error(external, code = 429)]
+/// struct RateLimited {
+///     #[api_error(value)]
+///     retry_after_secs: u32
+/// }
+///
+/// impl std::fmt::Display for RateLimited {
+///     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+///         write!(f, "Too many requests")
+///     }
+/// }
+///
+/// let e: ApiError = RateLimited { retry_after_secs: 30 }.into();
+/// assert_eq!(e.value, Some(serde_json::json!(30)));
+/// assert_eq!(e.error_code, Some("rate_limited".to_owned()));
 /// ```
 #[proc_macro_derive(ApiError, attributes(api_error))]
 pub fn derive_error(input: TokenStream) -> TokenStream {
@@ -0,0 +1,59 @@
+//! Measure how long it takes to route requests as the number of routes grows. The
+//! cost of routing a request should stay roughly flat regardless of the route count.
+//!
+//! Run with `cargo bench --bench router`.
+use criterion::{ criterion_group, criterion_main, BenchmarkId, Criterion };
+use seamless::{
+    api::Api,
+    handler::{ param::Path, request::Bytes, response::ToJson },
+};
+
+// An API with a mix of static routes and routes containing parameters. Each
+// "service" has 3 routes, so we end up with `services * 3` routes in total.
+fn api(services: usize) -> Api {
+    let mut api = Api::new_with_base_path("api");
+    for n in 0..services {
+        api.add(format!("service{}/items", n))
+            .handler(|| ToJson(0));
+        api.add(format!("service{}/items/{{id}}", n))
+            .handler(|Path(id): Path<usize>| ToJson(id));
+        api.add(format!("service{}/files/{{*path}}", n))
+            .handler(|Path(path): Path<String>| ToJson(path.len()));
+    }
+    api
+}
+
+fn request(path: &str) -> http::Request<Bytes> {
+    http::Request::get(path)
+        .body(Bytes::from_vec(vec![]))
+        .unwrap()
+}
+
+fn routing(c: &mut Criterion) {
+    let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+    let mut group = c.benchmark_group("routing");
+
+    for services in [10, 100, 1000, 3000] {
+        let api = api(services);
+        let routes = services * 3;
+        let last = services - 1;
+
+        let cases = [
+            ("static", format!("/api/service{}/items", last)),
+            ("param", format!("/api/service{}/items/1234", last)),
+            ("catch_all", format!("/api/service{}/files/a/b/c.txt", last)),
+            ("not_found", format!("/api/service{}/other", last)),
+        ];
+
+        for (name, path) in cases {
+            group.bench_with_input(BenchmarkId::new(name, routes), &path, |b, path| {
+                b.iter(|| runtime.block_on(api.handle(request(path))).is_ok())
+            });
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, routing);
+criterion_main!(benches);
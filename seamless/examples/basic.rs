@@ -63,7 +63,9 @@ async fn main() {
             code: 400,
             internal_message: "Division by zero".to_owned(),
             external_message: "Division by zero".to_owned(),
-            value: None
+            value: None,
+            problem_type: None,
+            error_code: None
         }
     );
 
@@ -34,6 +34,7 @@ async fn main() {
             "name": "maths/divide",
             "description": "Divide two numbers by each other",
             "method": "POST",
+            "operation_id": "mathsDivide",
             "request_type": {
                 "description": "Input consisting of two numbers",
                 "shape": {
@@ -69,7 +70,10 @@ async fn main() {
                         }
                     }
                 }
-            }
+            },
+            "errors": [
+                { "code": 400, "error_code": "maths.divide_by_zero" }
+            ]
         }
     ]);
     assert_eq!(serde_json::to_value(info).unwrap(), expected);
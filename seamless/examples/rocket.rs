@@ -79,6 +79,9 @@ impl Handler for SeamlessApi {
             Err(RouteError::NotFound(_req)) => {
                 Outcome::failure(Status::NotFound)
             },
+            Err(RouteError::MethodNotAllowed { .. }) => {
+                Outcome::failure(Status::MethodNotAllowed)
+            },
             Err(RouteError::Err(e)) => {
                 eprintln!("Whoops: {:?}", e);
                 Outcome::failure(Status::InternalServerError)
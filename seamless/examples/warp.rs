@@ -63,13 +63,6 @@ pub fn extract_request() -> impl Filter<Extract=(http::Request<RequestBytes>,),
         })
 }
 
-// If we get back an error from a `seamless` api route, we wrap it in
-// something that can be used as a custom warp Rejection. We'll want to
-// handle this properly as we would any other warp rejection.
-#[derive(Debug)]
-struct SeamlessApiError(seamless::ApiError);
-impl warp::reject::Reject for SeamlessApiError {}
-
 // Now, we can use `extract_request` above to convert a `seamless::Api` into a
 // warp filter like so:
 pub fn to_warp_filter(api: seamless::Api) -> BoxedFilter<(impl warp::Reply,)> {
@@ -81,10 +74,15 @@ pub fn to_warp_filter(api: seamless::Api) -> BoxedFilter<(impl warp::Reply,)> {
                 // In reality we should also check for the correct Content-Type and
                 // such. Perhaps we'd do that here, or perhaps we'd chain this with
                 // other warp filters.
-                api.handle(req).await.map_err(|e| {
+                api.handle(req).await.or_else(|e| {
+                    // Requests with the wrong method get a 405 response with an `Allow` header:
+                    if let Some(res) = e.method_not_allowed_response() {
+                        return Ok(res)
+                    }
                     match e {
-                        RouteError::NotFound(_) => warp::reject::not_found(),
-                        RouteError::Err(e) => warp::reject::custom(SeamlessApiError(e))
+                        // Errors from the API are handed back as JSON:
+                        RouteError::Err(e) => Ok(e.into_response()),
+                        _ => Err(warp::reject::not_found())
                     }
                 })
             }
@@ -1,24 +1,67 @@
-use std::collections::HashMap;
-use http::{ Request, Response, method::Method };
+use std::collections::{ BTreeMap, HashMap, HashSet };
+use std::panic::Location;
+use std::sync::Arc;
+use http::{ Request, Response, HeaderMap, method::Method, header, StatusCode };
 use serde::{ Serialize };
-use super::info::{ ApiBodyInfo };
-use super::error::ApiError;
-use crate::handler::{ Handler, IntoHandler, request::AsyncReadBody };
+use super::info::{ ApiBodyInfo, ApiBodyType };
+use super::error::{ ApiError, ApiErrorInfo, ApiErrors, merge_errors };
+use super::registry::{ TypeRegistry, rename_refs };
+use super::path::PathTemplate;
+use super::router::Router;
+use super::cors::CorsConfig;
+use super::middleware::{ Middleware, Next };
+use super::version::VersionSelector;
+use super::render::{ ErrorRenderer, JsonErrors };
+use crate::handler::{ Handler, IntoHandler, request::AsyncReadBody, param::PathParams, cookie::CookieJar };
+#[cfg(feature = "secure-cookies")]
+use crate::handler::cookie::CookieKey;
+use crate::codegen::camel_case;
 
 /// The entry point; you can create an instance of this and then add API routes to it
 /// using [`Self::add()`]. You can then get information about the routes that have been added
 /// using [`Self::info()`], or handle an [`http::Request`] using [`Self::handle()`].
 pub struct Api {
     base_path: String,
-    routes: HashMap<(Method,String),ResolvedApiRoute>
+    // Routes which aren't specific to any version:
+    routes: Router<ResolvedApiRoute>,
+    versioned_routes: BTreeMap<String, Router<ResolvedApiRoute>>,
+    versioning: Option<Box<dyn VersionSelector>>,
+    types: TypeRegistry,
+    cors: Option<CorsConfig>,
+    middleware: Vec<Arc<dyn Middleware>>,
+    error_renderer: Option<Box<dyn ErrorRenderer>>,
+    #[cfg(feature = "secure-cookies")]
+    cookie_key: Option<Arc<CookieKey>>
 }
 
-// An API route has the contents of `ResolvedHandler` but also a description.
+// Identifies a route which has been added.
+type RouteKey = (Option<String>, Method, PathTemplate);
+
+// An API route has the contents of `ResolvedHandler` but also the configuration
+// given via the `RouteBuilder`, information about the parameters in its path, and
+// where in the code it was added.
 struct ResolvedApiRoute {
-    description: String,
+    config: RouteConfig,
+    path_params: Vec<PathParamInfo>,
+    location: &'static Location<'static>,
     resolved_handler: Handler
 }
 
+// The configuration that a `RouteBuilder` collects.
+#[derive(Default)]
+struct RouteConfig {
+    description: String,
+    summary: String,
+    tags: Vec<String>,
+    deprecated: Option<String>,
+    operation_id: Option<String>,
+    meta: BTreeMap<String, serde_json::Value>,
+    version: Option<String>,
+    groups: Vec<RouteGroupInfo>,
+    cors: Option<CorsConfig>,
+    middleware: Vec<Arc<dyn Middleware>>
+}
+
 impl Api {
 
     /// Instantiate a new API.
@@ -35,10 +78,90 @@ impl Api {
     pub fn new_with_base_path<S: Into<String>>(base_path: S) -> Api {
         Api {
             base_path: base_path.into(),
-            routes: HashMap::new()
+            routes: Router::new(),
+            versioned_routes: BTreeMap::new(),
+            versioning: None,
+            types: TypeRegistry::default(),
+            cors: None,
+            middleware: vec![],
+            error_renderer: None,
+            #[cfg(feature = "secure-cookies")]
+            cookie_key: None
         }
     }
 
+    /// Add some [`Middleware`] which will run around the handler of every route. Middleware
+    /// added first runs first, and middleware added here runs before any middleware added to
+    /// groups of routes using [`RouteGroup::wrap()`] or individual routes using
+    /// [`RouteBuilder::wrap()`].
+    pub fn wrap<M: Middleware>(&mut self, middleware: M) {
+        self.middleware.push(Arc::new(middleware));
+    }
+
+    /// Decide which version of the API each request is for using the [`VersionSelector`]
+    /// provided. Requests for some version are handled by routes given that version using
+    /// [`RouteBuilder::version()`], or otherwise by routes which weren't given a version.
+    /// Routes for the requested version take priority over those without a version. Requests
+    /// for no particular version are only handled by routes without a version.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use seamless::{ Api, api::PathVersion, handler::{ request::Bytes, response::ToJson } };
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let mut api = Api::new();
+    /// api.versioning(PathVersion);
+    ///
+    /// api.add("users").version("v1").handler(|| ToJson("v1 users"));
+    /// api.add("users").version("v2").handler(|| ToJson("v2 users"));
+    /// api.add("status").handler(|| ToJson("ok"));
+    ///
+    /// let get = |path: &str| http::Request::get(path).body(Bytes::from_vec(vec![])).unwrap();
+    /// let res = api.handle(get("/v2/users")).await.unwrap();
+    /// assert_eq!(res.into_body(), br#""v2 users""#.to_vec());
+    /// let res = api.handle(get("/v1/status")).await.unwrap();
+    /// assert_eq!(res.into_body(), br#""ok""#.to_vec());
+    /// # })
+    /// ```
+    pub fn versioning<V: VersionSelector>(&mut self, selector: V) {
+        self.versioning = Some(Box::new(selector));
+    }
+
+    /// Configure how every route responds to cross-origin requests. This can be overridden
+    /// for individual routes using [`RouteBuilder::cors()`]. See [`CorsConfig`] for more.
+    pub fn cors(&mut self, config: CorsConfig) {
+        self.cors = Some(config);
+    }
+
+    /// Have errors emitted by route handlers (or middleware) converted into responses using the
+    /// [`ErrorRenderer`] provided, which [`Self::handle()`] then hands back in place of a
+    /// [`RouteError::Err`]. By default, errors are handed back as they are.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use seamless::{ Api, ApiError, api::JsonErrors, handler::{ request::Bytes, response::ToJson } };
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let mut api = Api::new();
+    /// api.render_errors(JsonErrors);
+    /// api.add("fails")
+    ///    .handler(|| Err::<ToJson<()>, _>(ApiError::not_authorized("no token")));
+    ///
+    /// let req = http::Request::get("/fails").body(Bytes::from_vec(vec![])).unwrap();
+    /// let res = api.handle(req).await.unwrap();
+    /// assert_eq!(res.status(), 403);
+    /// # })
+    /// ```
+    pub fn render_errors<R: ErrorRenderer>(&mut self, renderer: R) {
+        self.error_renderer = Some(Box::new(renderer));
+    }
+
+    /// Set the key used to sign and encrypt cookies (see [`crate::handler::cookie::Cookies`]).
+    #[cfg(feature = "secure-cookies")]
+    pub fn cookie_key(&mut self, key: CookieKey) {
+        self.cookie_key = Some(Arc::new(key));
+    }
+
     /// Add a new route to the API. You must provide a path to make this route available at,
     /// and are given back a [`RouteBuilder`] which can be used to give the route a handler
     /// and a description.
@@ -64,66 +187,561 @@ impl Api {
     ///     ToJson(ns.into_iter().sum())
     /// }
     /// ```
+    ///
+    /// Paths can contain parameters in place of any of their segments (for instance
+    /// `"users/{id}/posts"`), which can be obtained in the handler using
+    /// [`crate::handler::param::Path`]. The last segment can also be a catch-all
+    /// parameter (for instance `"files/{*path}"`), which captures the rest of the path.
+    /// Static segments take priority over parameters, which take priority over
+    /// catch-all parameters.
+    ///
+    /// # Panics
+    ///
+    /// Adding the handler will panic if the path is not valid, or if it conflicts with
+    /// the path of an existing route using the same method (for instance, `"users/{id}"`
+    /// and `"users/{name}"` conflict, since they would match exactly the same requests).
+    /// Use [`RouteBuilder::try_handler()`] to be handed back conflicts instead.
     pub fn add<P: Into<String>>(&mut self, path: P) -> RouteBuilder {
         RouteBuilder::new(self, path.into())
     }
 
-    // Add a route given the individual parts (for internal use)
-    fn add_parts<A, P: Into<String>, HandlerFn: IntoHandler<A>>(&mut self, path: P, description: String, handler_fn: HandlerFn) {
-        let resolved_handler = handler_fn.into_handler();
-        let mut path: String = path.into();
-        path = path.trim_matches('/').to_owned();
-        self.routes.insert((resolved_handler.method.clone(), path.into()), ResolvedApiRoute {
-            description,
+    /// Add a group of routes to the API. Every route added to the [`RouteGroup`] that is
+    /// handed to the function provided has its path prefixed with `prefix`, and is given any
+    /// description, middleware or CORS configuration that is set on the group. Groups are
+    /// listed in [`RouteInfo::groups`], so that documentation and generated clients can
+    /// namespace the routes in them.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use seamless::{ Api, api::{ ApiError, middleware_fn }, handler::{ body::FromJson, response::ToJson } };
+    /// let mut api = Api::new();
+    /// api.group("admin", |admin| {
+    ///     admin.description("Routes for administrators");
+    ///     admin.wrap(middleware_fn(|req, next| Box::pin(async move {
+    ///         if !req.headers().contains_key("x-admin-token") {
+    ///             return Err(ApiError::not_authorized("Admins only"))
+    ///         }
+    ///         next.run(req).await
+    ///     })));
+    ///
+    ///     // Available at "admin/users":
+    ///     admin.add("users")
+    ///         .handler(|| ToJson(vec!["Alice", "Bob"]));
+    /// });
+    ///
+    /// let info = api.info();
+    /// assert_eq!(info[0].name, "admin/users");
+    /// assert_eq!(info[0].groups[0].name, "admin");
+    /// ```
+    pub fn group<P: Into<String>, F: FnOnce(&mut RouteGroup)>(&mut self, prefix: P, f: F) {
+        let mut group = RouteGroup::new(self, "", prefix.into());
+        f(&mut group);
+        group.finish();
+    }
+
+    /// Add every route in `other` to this API, prefixing their paths with `prefix`. This
+    /// makes it possible for separate parts of an application to build up their own [`Api`]s,
+    /// which can then be combined into one. Each route keeps any middleware and CORS configuration
+    /// given to `other`, and is also subject to those given to this API. The base path and any
+    /// [`VersionSelector`] given to `other` are ignored.
+    ///
+    /// Any error renderer (see [`Self::render_errors()`]) or cookie key given to `other` is ignored
+    /// too; routes are handled using those of this API once mounted. If `other` renders errors
+    /// differently or uses signed or private cookies, give this API the same configuration.
+    ///
+    /// If any of the routes would conflict with an existing route (see [`Self::add()`]), a
+    /// [`RouteConflict`] is returned and no routes are added.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use seamless::{ Api, handler::response::ToJson };
+    /// let mut users = Api::new();
+    /// users.add("list").handler(|| ToJson(vec!["Alice", "Bob"]));
+    ///
+    /// let mut api = Api::new_with_base_path("api");
+    /// api.mount("users", users).unwrap();
+    ///
+    /// assert_eq!(api.info()[0].name, "users/list");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the prefix is not a valid path, or if it contains a parameter with the same
+    /// name as one in the path of some route in `other`.
+    pub fn mount<P: Into<String>>(&mut self, prefix: P, other: Api) -> Result<(), RouteConflict> {
+        let prefix: String = prefix.into();
+        let prefix = prefix.trim_matches('/');
+        let Api { routes, versioned_routes, types, cors, middleware, .. } = other;
+
+        // Check that none of the routes conflict before adding any of them:
+        let mut new_routes = vec![];
+        let routes = std::iter::once(routes).chain(versioned_routes.into_values()).flat_map(Router::into_routes);
+        for (method, template, route) in routes {
+            let path = join_path(prefix, &template.to_string());
+            let template = PathTemplate::parse(&path)
+                .unwrap_or_else(|e| panic!("Invalid route path '{}': {}", path, e));
+            let existing = self.router(route.config.version.as_deref())
+                .and_then(|router| router.existing(&method, &template));
+            if let Some((existing_path, existing)) = existing {
+                return Err(RouteConflict {
+                    method,
+                    path,
+                    location: route.location,
+                    existing_path: existing_path.to_string(),
+                    existing_location: existing.location
+                })
+            }
+            new_routes.push((path, route));
+        }
+
+        // Named types may need renaming to avoid clashing with those we already have:
+        let renames = self.types.merge(types);
+        for (path, route) in new_routes {
+            let ResolvedApiRoute { mut config, mut resolved_handler, location, .. } = route;
+            resolved_handler.request_type = rename_refs(&resolved_handler.request_type, &renames);
+            resolved_handler.response_type = rename_refs(&resolved_handler.response_type, &renames);
+            if let Some(path) = &mut resolved_handler.param_info.path {
+                *path = rename_refs(path, &renames);
+            }
+            if let Some(query) = &mut resolved_handler.param_info.query {
+                *query = rename_refs(query, &renames);
+            }
+            for header in &mut resolved_handler.param_info.headers {
+                header.ty = rename_refs(&header.ty, &renames);
+            }
+            for value in resolved_handler.errors.iter_mut().filter_map(|error| error.value.as_mut()) {
+                *value = rename_refs(value, &renames);
+            }
+            config.cors = config.cors.or_else(|| cors.clone());
+            config.middleware = middleware.iter().cloned().chain(config.middleware).collect();
+            self.add_route(path, config, resolved_handler, location, false)
+                .expect("conflicts were checked above");
+        }
+        Ok(())
+    }
+
+    /// Add every route in `other` to this API. This is the same as calling
+    /// [`Self::mount()`] with an empty prefix, so likewise, any error renderer or
+    /// cookie key given to `other` is ignored in favour of those of this API.
+    pub fn merge(&mut self, other: Api) -> Result<(), RouteConflict> {
+        self.mount("", other)
+    }
+
+    // Resolve a handler function into a `Handler` (for internal use).
+    fn resolve_handler<A, HandlerFn: IntoHandler<A>>(&mut self, handler_fn: HandlerFn) -> Handler {
+        // Named types used in the request and response are stored in our registry
+        // and referred to by name in the handler:
+        self.types.collect(|| handler_fn.into_handler())
+    }
+
+    // Add a route given the individual parts (for internal use). If a route with the same method
+    // and path exists already, it's replaced if `replace` is true; any other conflict is an error.
+    fn add_route(&mut self, path: String, config: RouteConfig, resolved_handler: Handler, location: &'static Location<'static>, replace: bool) -> Result<RouteKey, RouteConflict> {
+        let path = path.trim_matches('/').to_owned();
+
+        let template = PathTemplate::parse(&path)
+            .unwrap_or_else(|e| panic!("Invalid route path '{}': {}", path, e));
+        let method = resolved_handler.method.clone();
+        let version = config.version.clone();
+        let existing = self.router(version.as_deref())
+            .and_then(|router| router.existing(&method, &template));
+        if let Some((existing_path, existing)) = existing {
+            if !replace || existing_path != &template {
+                return Err(RouteConflict {
+                    method,
+                    path,
+                    location,
+                    existing_path: existing_path.to_string(),
+                    existing_location: existing.location
+                })
+            }
+        }
+
+        let path_params = self.path_params(&template, resolved_handler.param_info.path.as_ref());
+        let route = ResolvedApiRoute {
+            config,
+            path_params,
+            location,
             resolved_handler
-        });
+        };
+        self.router_mut(version.as_deref()).insert(method.clone(), template.clone(), route)
+            .expect("conflicts were checked above");
+        Ok((version, method, template))
+    }
+
+    // The routes for the version given, or those without a version.
+    fn router(&self, version: Option<&str>) -> Option<&Router<ResolvedApiRoute>> {
+        match version {
+            Some(version) => self.versioned_routes.get(version),
+            None => Some(&self.routes)
+        }
+    }
+
+    fn router_mut(&mut self, version: Option<&str>) -> &mut Router<ResolvedApiRoute> {
+        match version {
+            Some(version) => self.versioned_routes.entry(version.to_owned()).or_insert_with(Router::new),
+            None => &mut self.routes
+        }
+    }
+
+    // Find the route matching some request. Routes for the version asked for take
+    // priority over those without a version.
+    fn find(&self, version: Option<&str>, method: &Method, path: &str) -> Option<(&ResolvedApiRoute, Vec<(String,String)>)> {
+        version.and_then(|v| self.versioned_routes.get(v))
+            .and_then(|router| router.find(method, path))
+            .or_else(|| self.routes.find(method, path))
+    }
+
+    // Work out the type of each parameter in the path, given the shape of the
+    // parameters that the handler asks for. Anything we don't know about is a string.
+    fn path_params(&self, template: &PathTemplate, shape: Option<&ApiBodyInfo>) -> Vec<PathParamInfo> {
+        let mut shape = shape.cloned();
+        while let Some(ApiBodyInfo { ty: ApiBodyType::Ref { name }, .. }) = &shape {
+            shape = self.types.definitions().get(name).cloned();
+        }
+
+        let names: Vec<&str> = template.param_names().collect();
+        names.iter().enumerate().map(|(idx, name)| {
+            let ty = match shape.as_ref().map(|s| &s.ty) {
+                Some(ApiBodyType::Object { keys }) => keys.get(*name).cloned(),
+                Some(ApiBodyType::TupleOf { values }) => values.get(idx).cloned(),
+                Some(_) if names.len() == 1 => shape.clone(),
+                _ => None
+            };
+            PathParamInfo {
+                name: name.to_string(),
+                ty: ty.unwrap_or(ApiBodyInfo { description: String::new(), ty: ApiBodyType::String })
+            }
+        }).collect()
     }
 
     /// Match an incoming [`http::Request`] against our API routes and run the relevant handler if a
     /// matching one is found. We'll get back bytes representing a JSON response back if all goes ok,
     /// else we'll get back a [`RouteError`], which will either be [`RouteError::NotFound`] if no matching
-    /// route was found, or a [`RouteError::Err`] if a matching route was found, but that handler emitted
+    /// route was found, [`RouteError::MethodNotAllowed`] if routes match the path but not the method of
+    /// the request, or a [`RouteError::Err`] if a matching route was found, but that handler emitted
     /// an error.
+    ///
+    /// Unless a route has been added for them, `OPTIONS` requests to any known path are answered
+    /// with a `204 No Content` response with an `Allow` header listing the allowed methods, and `HEAD`
+    /// requests are handled by the corresponding `GET` route, with the response body removed.
+    ///
+    /// If CORS has been configured (see [`Self::cors()`]), preflight requests are answered, and
+    /// CORS headers are added to successful responses (and to error responses, if
+    /// [`Self::render_errors()`] has been used).
+    ///
+    /// Any [`Middleware`] that applies to the matching route is run around its handler. If
+    /// versioning has been configured (see [`Self::versioning()`]), the route is chosen based
+    /// on the version that the request asks for.
     pub async fn handle<Body: AsyncReadBody>(&self, req: Request<Body>) -> Result<Response<Vec<u8>>, RouteError<Body, ApiError>> {
         let base_path = &self.base_path.trim_start_matches('/');
         let req_path = req.uri().path().trim_start_matches('/');
 
         if req_path.starts_with(base_path) {
             // Ensure that the method and path suffix lines up as expected:
-            let req_method = req.method().into();
-            let req_path_tail = req_path[base_path.len()..].trim_start_matches('/').to_owned();
+            let req_method = req.method().clone();
+            let req_path_tail = req_path[base_path.len()..].trim_start_matches('/');
+
+            // Work out which version of the API (if any) the request is for:
+            let (version, req_path_tail) = match &self.versioning {
+                Some(selector) => selector.select(req.headers(), req_path_tail),
+                None => (None, req_path_tail)
+            };
+            let version = version.as_deref();
+            let req_path_tail = req_path_tail.to_owned();
 
             // Turn req body into &mut dyn AsyncReadBody:
             let (req_parts, mut req_body) = req.into_parts();
-            let dyn_req = Request::from_parts(req_parts, &mut req_body as &mut dyn AsyncReadBody);
+            let mut dyn_req = Request::from_parts(req_parts, &mut req_body as &mut dyn AsyncReadBody);
+
+            // Answer CORS preflight requests:
+            if req_method == Method::OPTIONS {
+                if let Some(res) = self.preflight_response(dyn_req.headers(), version, &req_path_tail) {
+                    return Ok(res)
+                }
+            }
+
+            // HEAD requests fall back to being handled by GET routes:
+            let mut route = self.find(version, &req_method, &req_path_tail);
+            let is_head_fallback = route.is_none() && req_method == Method::HEAD;
+            if is_head_fallback {
+                route = self.find(version, &Method::GET, &req_path_tail);
+            }
 
-            if let Some(route) = self.routes.get(&(req_method,req_path_tail)) {
-                (route.resolved_handler.handler)(dyn_req).await.map_err(RouteError::Err)
+            if let Some((route, params)) = route {
+                dyn_req.extensions_mut().insert(PathParams(params));
+
+                // Handlers can set cookies via this, which we then add to the response:
+                #[cfg(feature = "secure-cookies")]
+                let cookies = CookieJar::with_key(self.cookie_key.clone());
+                #[cfg(not(feature = "secure-cookies"))]
+                let cookies = CookieJar::default();
+                dyn_req.extensions_mut().insert(cookies.clone());
+
+                let cors_headers = route.config.cors.as_ref()
+                    .or(self.cors.as_ref())
+                    .map(|cors| cors.response_headers(dyn_req.headers()));
+
+                let uri = dyn_req.uri().clone();
+                let next = Next::new(&self.middleware, &route.config.middleware, &*route.resolved_handler.handler);
+                let mut res = match next.run(dyn_req).await {
+                    Ok(mut res) => {
+                        cookies.add_headers(res.headers_mut());
+                        res
+                    },
+                    Err(e) => match &self.error_renderer {
+                        Some(renderer) => renderer.render(e, &uri),
+                        None => return Err(RouteError::Err(e))
+                    }
+                };
+                if let Some(cors_headers) = cors_headers {
+                    add_headers(res.headers_mut(), cors_headers);
+                }
+                Ok(if is_head_fallback { without_body(res) } else { res })
             } else {
+                let allowed = self.allowed_methods(version, &req_path_tail);
                 let (req_parts, _) = dyn_req.into_parts();
-                Err(RouteError::NotFound(Request::from_parts(req_parts, req_body)))
+                let request = Request::from_parts(req_parts, req_body);
+                if allowed.is_empty() {
+                    Err(RouteError::NotFound(request))
+                } else if req_method == Method::OPTIONS {
+                    Ok(allow_response(StatusCode::NO_CONTENT, &allowed))
+                } else {
+                    Err(RouteError::MethodNotAllowed { allowed, request })
+                }
             }
         } else {
             Err(RouteError::NotFound(req))
         }
     }
 
+    // If the headers given are those of a CORS preflight request for a route that allows
+    // it, return the response to send back.
+    fn preflight_response(&self, headers: &HeaderMap, version: Option<&str>, path: &str) -> Option<Response<Vec<u8>>> {
+        let requested_method: Method = headers.get(header::ACCESS_CONTROL_REQUEST_METHOD)?
+            .as_bytes()
+            .try_into()
+            .ok()?;
+        let (route, _) = self.find(version, &requested_method, path)
+            .or_else(|| if requested_method == Method::HEAD { self.find(version, &Method::GET, path) } else { None })?;
+        let cors = route.config.cors.as_ref().or(self.cors.as_ref())?;
+        let cors_headers = cors.preflight_headers(headers, &requested_method, &route.resolved_handler.method)?;
+
+        let mut res = Response::new(vec![]);
+        *res.status_mut() = StatusCode::NO_CONTENT;
+        *res.headers_mut() = cors_headers;
+        Some(res)
+    }
+
+    // The methods allowed for some path. As well as the methods of the routes matching it,
+    // this includes the methods that we handle automatically (HEAD and OPTIONS).
+    fn allowed_methods(&self, version: Option<&str>, path: &str) -> Vec<Method> {
+        let mut allowed = self.routes.allowed_methods(path);
+        if let Some(router) = version.and_then(|v| self.versioned_routes.get(v)) {
+            allowed.extend(router.allowed_methods(path));
+        }
+        if allowed.is_empty() {
+            return allowed
+        }
+        if allowed.contains(&Method::GET) {
+            allowed.push(Method::HEAD);
+        }
+        allowed.push(Method::OPTIONS);
+        allowed.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        allowed.dedup();
+        allowed
+    }
+
     /// Return information about the API routes that have been defined so far.
+    ///
+    /// The request and response types of each route are described in full. Types which
+    /// refer to themselves cannot be expanded in this way, and so those self references
+    /// are left as [`crate::ApiBodyType::Ref`]s; use [`Self::info_with_definitions()`]
+    /// to obtain the definitions that these refer to.
     pub fn info(&self) -> Vec<RouteInfo> {
+        self.resolve_info(self.route_info(None))
+    }
+
+    /// Return information about the API routes which handle requests for the version provided
+    /// (see [`Self::versioning()`]). This includes routes given that version, and routes without
+    /// a version unless they're overridden by a route for that version. This is otherwise the
+    /// same as [`Self::info()`].
+    pub fn info_for_version(&self, version: &str) -> Vec<RouteInfo> {
+        self.resolve_info(self.route_info(Some(version)))
+    }
+
+    fn resolve_info(&self, mut info: Vec<RouteInfo>) -> Vec<RouteInfo> {
+        for route in &mut info {
+            route.request_type = self.types.resolve(&route.request_type);
+            route.response_type = self.types.resolve(&route.response_type);
+            for param in &mut route.path_params {
+                param.ty = self.types.resolve(&param.ty);
+            }
+            if let Some(query) = &mut route.query_type {
+                *query = self.types.resolve(query);
+            }
+            for header in &mut route.headers {
+                header.ty = self.types.resolve(&header.ty);
+            }
+            for value in route.errors.iter_mut().filter_map(|error| error.value.as_mut()) {
+                *value = self.types.resolve(value);
+            }
+        }
+        info
+    }
+
+    /// Return information about the API routes that have been defined so far. Unlike
+    /// [`Self::info()`], every named type (ie those using the [`macro@crate::ApiBody`] macro)
+    /// is described just once in the `definitions` that are handed back, and is referred
+    /// to by name everywhere else via [`crate::ApiBodyType::Ref`].
+    pub fn info_with_definitions(&self) -> ApiInfo {
+        ApiInfo {
+            routes: self.route_info(None),
+            definitions: self.types.definitions().clone(),
+            error_response: self.error_response_info()
+        }
+    }
+
+    /// Like [`Self::info_with_definitions()`], but only includes the routes which handle
+    /// requests for the version provided; see [`Self::info_for_version()`]. This can be handed
+    /// to a code generator in order to generate a client for a single version of the API.
+    pub fn info_with_definitions_for_version(&self, version: &str) -> ApiInfo {
+        ApiInfo {
+            routes: self.route_info(Some(version)),
+            definitions: self.types.definitions().clone(),
+            error_response: self.error_response_info()
+        }
+    }
+
+    // Errors are described as they'll be rendered. If they aren't rendered by us, we
+    // assume that they'll be handed back using `ApiError::into_response()`.
+    fn error_response_info(&self) -> ErrorResponseInfo {
+        let renderer = self.error_renderer.as_deref().unwrap_or(&JsonErrors);
+        ErrorResponseInfo {
+            content_type: renderer.content_type().to_owned(),
+            body: renderer.response_body_info()
+        }
+    }
+
+    // Information about every route, or only those which handle the version given.
+    fn route_info(&self, version: Option<&str>) -> Vec<RouteInfo> {
+        let routes: Vec<_> = match version {
+            None => {
+                let versioned = self.versioned_routes.values().flat_map(|router| router.routes());
+                self.routes.routes().into_iter().chain(versioned).collect()
+            },
+            Some(version) => {
+                let versioned = self.versioned_routes.get(version);
+                let unversioned = self.routes.routes().into_iter().filter(|(method, path, _)| {
+                    versioned.and_then(|router| router.existing(method, path)).is_none()
+                });
+                versioned.map(|router| router.routes()).unwrap_or_default().into_iter().chain(unversioned).collect()
+            }
+        };
+
         let mut info = vec![];
-        for ((_method,key), val) in &self.routes {
+        for (_method, path, val) in routes {
             info.push(RouteInfo {
-                name: key.to_owned(),
+                name: path.to_string(),
                 method: format!("{}", &val.resolved_handler.method),
-                description: val.description.clone(),
+                description: val.config.description.clone(),
+                summary: val.config.summary.clone(),
+                tags: val.config.tags.clone(),
+                deprecated: val.config.deprecated.clone(),
+                operation_id: val.config.operation_id.clone().unwrap_or_default(),
+                meta: val.config.meta.clone(),
+                version: val.config.version.clone(),
+                groups: val.config.groups.clone(),
                 request_type: val.resolved_handler.request_type.clone(),
-                response_type: val.resolved_handler.response_type.clone()
+                response_type: val.resolved_handler.response_type.clone(),
+                errors: val.resolved_handler.errors.clone(),
+                path_params: val.path_params.clone(),
+                query_type: val.resolved_handler.param_info.query.clone(),
+                headers: val.resolved_handler.param_info.headers.clone()
             });
         }
-        info.sort_by(|a,b| a.name.cmp(&b.name));
+        info.sort_by(|a, b| (&a.name, &a.method, &a.version).cmp(&(&b.name, &b.method, &b.version)));
+        assign_operation_ids(&mut info);
         info
     }
 
+    /// Return an OpenAPI 3.1 document describing the API routes that have been defined so far.
+    /// See [`crate::codegen::openapi`] for more information.
+    ///
+    /// The paths of routes given a version (see [`RouteBuilder::version()`]) are prefixed with
+    /// that version if it's part of the request path (see [`PathVersion`](crate::api::PathVersion)).
+    /// Otherwise, such routes can't be told apart from each other by their path, and so they're
+    /// left out; use [`Self::openapi_for_version()`] to describe them.
+    pub fn openapi(&self) -> serde_json::Value {
+        let mut info = self.info_with_definitions();
+        info.routes.retain_mut(|route| {
+            let version = match &route.version {
+                Some(version) => version,
+                None => return true
+            };
+            match self.version_path_prefix(version) {
+                Some(prefix) => {
+                    route.name = format!("{}/{}", prefix, route.name);
+                    true
+                },
+                None => false
+            }
+        });
+        crate::codegen::openapi::generate(&self.base_path, &info)
+    }
+
+    /// Return an OpenAPI 3.1 document describing the API routes that handle requests for the
+    /// version provided (see [`Self::info_for_version()`]). If the version is part of the
+    /// request path (see [`PathVersion`](crate::api::PathVersion)), it's added to the path of
+    /// every route.
+    pub fn openapi_for_version(&self, version: &str) -> serde_json::Value {
+        let base_path = match self.version_path_prefix(version) {
+            Some(prefix) => format!("{}/{}", self.base_path.trim_end_matches('/'), prefix),
+            None => self.base_path.clone()
+        };
+        crate::codegen::openapi::generate(&base_path, &self.info_with_definitions_for_version(version))
+    }
+
+    // The prefix that requests for some version are given, if the version is part of the path.
+    fn version_path_prefix(&self, version: &str) -> Option<String> {
+        self.versioning.as_ref().and_then(|versioning| versioning.path_prefix(version))
+    }
+
+}
+
+// Routes which weren't given an operation ID are given one based on their path. Routes
+// are expected to have been sorted already, so that this is deterministic.
+fn assign_operation_ids(info: &mut [RouteInfo]) {
+    let mut methods: HashMap<&str, HashSet<&str>> = HashMap::new();
+    for route in info.iter() {
+        methods.entry(&route.name).or_default().insert(&route.method);
+    }
+    let shares_path: Vec<bool> = info.iter().map(|route| methods[route.name.as_str()].len() > 1).collect();
+
+    let mut taken: HashSet<String> = info.iter()
+        .map(|route| route.operation_id.clone())
+        .filter(|id| !id.is_empty())
+        .collect();
+    for (route, shares_path) in info.iter_mut().zip(shares_path) {
+        if !route.operation_id.is_empty() {
+            continue
+        }
+        let base = if shares_path {
+            camel_case(&format!("{} {}", route.name, route.method.to_ascii_lowercase()))
+        } else {
+            camel_case(&route.name)
+        };
+        let mut id = base.clone();
+        let mut n = 2;
+        while taken.contains(&id) {
+            id = format!("{}{}", base, n);
+            n += 1;
+        }
+        taken.insert(id.clone());
+        route.operation_id = id;
+    }
 }
 
 /// Add a new API route by providing a description (optional but encouraged)
@@ -152,30 +770,256 @@ impl Api {
 pub struct RouteBuilder<'a> {
     api: &'a mut Api,
     path: String,
-    description: String
+    config: RouteConfig,
+    // Errors declared using `errors()`:
+    errors: Vec<ApiErrorInfo>,
+    // Set if the route is being added to a group:
+    group_routes: Option<&'a mut Vec<RouteKey>>
 }
 impl <'a> RouteBuilder<'a> {
     fn new(api: &'a mut Api, path: String) -> Self {
-        RouteBuilder { api, path, description: String::new() }
+        RouteBuilder { api, path, config: RouteConfig::default(), errors: vec![], group_routes: None }
     }
     /// Add a description to the API route.
     pub fn description<S: Into<String>>(mut self, desc: S) -> Self {
-        self.description = desc.into();
+        self.config.description = desc.into();
+        self
+    }
+    /// Add a short summary of what the API route does, to complement the description.
+    pub fn summary<S: Into<String>>(mut self, summary: S) -> Self {
+        self.config.summary = summary.into();
+        self
+    }
+    /// Add some tags to the API route, which documentation can use to group routes together.
+    pub fn tags<S: Into<String>, I: IntoIterator<Item = S>>(mut self, tags: I) -> Self {
+        self.config.tags.extend(tags.into_iter().map(|t| t.into()));
+        self
+    }
+    /// Mark the API route as deprecated, with a message which should explain
+    /// what to use instead (for instance `"use v2/foo"`).
+    pub fn deprecated<S: Into<String>>(mut self, message: S) -> Self {
+        self.config.deprecated = Some(message.into());
+        self
+    }
+    /// Set an identifier for the API route, which documentation and generated clients
+    /// can use to refer to it (for instance, the TypeScript client uses this to name the
+    /// function for the route). This should be unique across the API. Routes which aren't
+    /// given one are given one based on their path; see [`RouteInfo::operation_id`].
+    pub fn operation_id<S: Into<String>>(mut self, id: S) -> Self {
+        self.config.operation_id = Some(id.into());
+        self
+    }
+    /// Make this route handle requests for the version of the API provided, rather than
+    /// requests for any version. See [`Api::versioning()`] for more.
+    pub fn version<S: Into<String>>(mut self, version: S) -> Self {
+        self.config.version = Some(version.into());
+        self
+    }
+    /// Attach some arbitrary metadata to the API route, which is handed back in
+    /// [`RouteInfo::meta`]. Setting the same key again replaces the previous value.
+    pub fn meta<S: Into<String>, V: Into<serde_json::Value>>(mut self, key: S, value: V) -> Self {
+        self.config.meta.insert(key.into(), value.into());
+        self
+    }
+    /// Configure how this route responds to cross-origin requests, overriding
+    /// any configuration given to [`Api::cors()`].
+    pub fn cors(mut self, config: CorsConfig) -> Self {
+        self.config.cors = Some(config);
+        self
+    }
+    /// Add some [`Middleware`] which will run around the handler of this route. This
+    /// runs after any middleware added using [`Api::wrap()`] or [`RouteGroup::wrap()`].
+    pub fn wrap<M: Middleware>(mut self, middleware: M) -> Self {
+        self.config.middleware.push(Arc::new(middleware));
+        self
+    }
+    /// Declare some errors that this route might hand back on top of those described by its
+    /// handler (such as errors from middleware wrapping it), so that they're described in
+    /// [`RouteInfo::errors`] too. This can be called more than once to declare the errors of
+    /// several types. Deriving [`ApiError`](seamless_macros::ApiError) on a type implements
+    /// [`ApiErrors`] for it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use seamless::{ Api, ApiError, api::middleware_fn, handler::response::ToJson };
+    /// #[derive(ApiError, Debug, thiserror::Error)]
+    /// #[error("Not logged in")]
+    /// #[api_error(external, code = 401)]
+    /// struct NotLoggedIn;
+    ///
+    /// let mut api = Api::new();
+    /// api.add("secret")
+    ///     .wrap(middleware_fn(|req, next| Box::pin(async move {
+    ///         if !req.headers().contains_key("authorization") {
+    ///             return Err(NotLoggedIn.into())
+    ///         }
+    ///         next.run(req).await
+    ///     })))
+    ///     .errors::<NotLoggedIn>()
+    ///     .handler(|| ToJson("secret"));
+    ///
+    /// let info = api.info();
+    /// assert_eq!(info[0].errors[0].code, 401);
+    /// ```
+    pub fn errors<E: ApiErrors>(mut self) -> Self {
+        // Named types used in error values are stored in our registry, as with handlers:
+        let errors = self.api.types.collect(E::api_error_info);
+        self.errors = merge_errors(self.errors, errors);
         self
     }
     /// Add a handler to the API route. Until this has been added, the route
     /// doesn't "exist".
+    ///
+    /// # Panics
+    ///
+    /// Panics if the route conflicts with an existing route (see [`Api::add()`]). Adding a route
+    /// with the same method and path as an existing route replaces it in release builds, but
+    /// is assumed to be a mistake and panics in debug builds. Use [`Self::try_handler()`] to be
+    /// handed back any conflict instead.
+    #[track_caller]
     pub fn handler<A, HandlerFn: IntoHandler<A>>(self, handler: HandlerFn) {
-        self.api.add_parts(self.path, self.description, handler);
+        let replace = !cfg!(debug_assertions);
+        if let Err(conflict) = self.add_handler(handler, replace) {
+            panic!("{}", conflict);
+        }
+    }
+    /// Add a handler to the API route, or hand back a [`RouteConflict`] (and leave the API
+    /// unchanged) if the route conflicts with an existing route. A route conflicts with
+    /// another if it has the same method and its path would match exactly the same requests.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use seamless::{ Api, handler::{ param::Path, response::ToJson } };
+    /// let mut api = Api::new();
+    /// api.add("users/{id}")
+    ///     .try_handler(|Path(id): Path<usize>| ToJson(id))
+    ///     .unwrap();
+    ///
+    /// let conflict = api.add("users/{name}")
+    ///     .try_handler(|Path(name): Path<String>| ToJson(name))
+    ///     .unwrap_err();
+    /// assert_eq!(conflict.existing_path, "users/{id}");
+    /// ```
+    #[track_caller]
+    pub fn try_handler<A, HandlerFn: IntoHandler<A>>(self, handler: HandlerFn) -> Result<(), RouteConflict> {
+        self.add_handler(handler, false)
+    }
+    #[track_caller]
+    fn add_handler<A, HandlerFn: IntoHandler<A>>(self, handler: HandlerFn, replace: bool) -> Result<(), RouteConflict> {
+        let location = Location::caller();
+        let mut handler = self.api.resolve_handler(handler);
+        handler.errors = merge_errors(handler.errors, self.errors);
+        let route = self.api.add_route(self.path, self.config, handler, location, replace)?;
+        if let Some(routes) = self.group_routes {
+            routes.push(route);
+        }
+        Ok(())
     }
 }
 
-/// A route is either not found, or we attempted to run it and ran into
-/// an issue.
+/// A group of routes which share a path prefix, and optionally a description,
+/// middleware and CORS configuration. This is handed to the function given to
+/// [`Api::group()`]; see that for more.
+pub struct RouteGroup<'a> {
+    api: &'a mut Api,
+    // The prefix including those of any enclosing groups:
+    prefix: String,
+    info: RouteGroupInfo,
+    cors: Option<CorsConfig>,
+    middleware: Vec<Arc<dyn Middleware>>,
+    // The routes added to this group (or groups inside it):
+    routes: Vec<RouteKey>
+}
+impl <'a> RouteGroup<'a> {
+    fn new(api: &'a mut Api, parent_prefix: &str, prefix: String) -> Self {
+        let name = prefix.trim_matches('/').to_owned();
+        RouteGroup {
+            api,
+            prefix: join_path(parent_prefix, &name),
+            info: RouteGroupInfo { name, description: String::new() },
+            cors: None,
+            middleware: vec![],
+            routes: vec![]
+        }
+    }
+    /// Add a description to the group.
+    pub fn description<S: Into<String>>(&mut self, desc: S) {
+        self.info.description = desc.into();
+    }
+    /// Configure how the routes in this group respond to cross-origin requests, overriding
+    /// any configuration given to [`Api::cors()`]. Routes can override this in turn using
+    /// [`RouteBuilder::cors()`].
+    pub fn cors(&mut self, config: CorsConfig) {
+        self.cors = Some(config);
+    }
+    /// Add some [`Middleware`] which will run around the handler of every route in this group,
+    /// including those added before it. This runs after any middleware added using [`Api::wrap()`]
+    /// or to any enclosing group, and before any added to individual routes.
+    pub fn wrap<M: Middleware>(&mut self, middleware: M) {
+        self.middleware.push(Arc::new(middleware));
+    }
+    /// Add a new route to the group. This is like [`Api::add()`], except that the path
+    /// provided is relative to the group prefix.
+    pub fn add<P: Into<String>>(&mut self, path: P) -> RouteBuilder<'_> {
+        RouteBuilder {
+            api: self.api,
+            path: join_path(&self.prefix, path.into().trim_start_matches('/')),
+            config: RouteConfig::default(),
+            errors: vec![],
+            group_routes: Some(&mut self.routes)
+        }
+    }
+    /// Add a group of routes inside this one. This is like [`Api::group()`], except that
+    /// the prefix provided is relative to the prefix of this group.
+    pub fn group<P: Into<String>, F: FnOnce(&mut RouteGroup)>(&mut self, prefix: P, f: F) {
+        let mut group = RouteGroup::new(self.api, &self.prefix, prefix.into());
+        f(&mut group);
+        let routes = group.finish();
+        self.routes.extend(routes);
+    }
+    // Routes are added to the API straight away, so that any conflicts are noticed. Once
+    // the group is finished with, we apply the group configuration to each of them.
+    fn finish(self) -> Vec<RouteKey> {
+        let RouteGroup { api, info, cors, middleware, routes, .. } = self;
+        for (version, method, template) in &routes {
+            if let Some(route) = api.router_mut(version.as_deref()).get_mut(method, template) {
+                let config = &mut route.config;
+                config.groups.insert(0, info.clone());
+                config.cors = config.cors.take().or_else(|| cors.clone());
+                config.middleware = middleware.iter().cloned().chain(config.middleware.drain(..)).collect();
+            }
+        }
+        routes
+    }
+}
+
+// Join two parts of a route path together.
+fn join_path(prefix: &str, path: &str) -> String {
+    match (prefix, path) {
+        ("", path) => path.to_owned(),
+        (prefix, "") => prefix.to_owned(),
+        (prefix, path) => format!("{}/{}", prefix, path)
+    }
+}
+
+/// A route is either not found, found but not for the method provided,
+/// or we attempted to run it and ran into an issue.
 pub enum RouteError<B, E> {
     /// No route matched the provided request,
     /// so we hand it back.
     NotFound(Request<B>),
+    /// Some routes matched the path of the provided request, but none of
+    /// them expect the method that it was sent with. We hand back the methods
+    /// that they do expect, and the request. See
+    /// [`RouteError::method_not_allowed_response()`].
+    MethodNotAllowed {
+        /// The methods that routes matching the request path expect.
+        allowed: Vec<Method>,
+        /// The request that was provided.
+        request: Request<B>
+    },
     /// The matching route failed; this is the error.
     Err(E)
 }
@@ -184,6 +1028,7 @@ impl <B, E: std::fmt::Debug> std::fmt::Debug for RouteError<B, E> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             RouteError::NotFound(..) => f.debug_tuple("RouteError::NotFound").finish(),
+            RouteError::MethodNotAllowed { allowed, .. } => f.debug_struct("RouteError::MethodNotAllowed").field("allowed", allowed).finish(),
             RouteError::Err(e) => f.debug_tuple("RouteError::Err").field(e).finish()
         }
     }
@@ -199,9 +1044,118 @@ impl <B, E> RouteError<B, E> {
     pub fn unwrap_err(self) -> E {
         match self {
             RouteError::Err(e) => e,
-            _ => panic!("Attempt to unwrap_api_err on RouteError that is NotFound")
+            RouteError::NotFound(..) => panic!("Attempt to unwrap_api_err on RouteError that is NotFound"),
+            RouteError::MethodNotAllowed { .. } => panic!("Attempt to unwrap_api_err on RouteError that is MethodNotAllowed")
         }
     }
+
+    /// If the `RouteError` is [`RouteError::MethodNotAllowed`], hand back a `405 Method Not Allowed`
+    /// response with an `Allow` header listing the methods that are allowed. Otherwise, hand back
+    /// `None`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use seamless::{ Api, handler::{ body::FromJson, request::Bytes, response::ToJson } };
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let mut api = Api::new();
+    /// api.add("echo")
+    ///    .handler(|body: FromJson<String>| ToJson(body.0));
+    ///
+    /// let req = http::Request::get("/echo")
+    ///     .body(Bytes::from_vec(vec![]))
+    ///     .unwrap();
+    ///
+    /// let res = api.handle(req).await.unwrap_err().method_not_allowed_response().unwrap();
+    /// assert_eq!(res.status(), 405);
+    /// assert_eq!(res.headers()["allow"], "OPTIONS, POST");
+    /// # })
+    /// ```
+    pub fn method_not_allowed_response(&self) -> Option<Response<Vec<u8>>> {
+        let allowed = match self {
+            RouteError::MethodNotAllowed { allowed, .. } => allowed,
+            _ => return None
+        };
+        Some(allow_response(StatusCode::METHOD_NOT_ALLOWED, allowed))
+    }
+}
+
+/// This is returned when a route can't be added to an [`Api`] because it would
+/// match exactly the same requests as an existing route.
+#[derive(Debug,Clone,PartialEq)]
+pub struct RouteConflict {
+    /// The method of the route.
+    pub method: Method,
+    /// The path of the route.
+    pub path: String,
+    /// Where in the code the route was added.
+    pub location: &'static Location<'static>,
+    /// The path of the existing route that it conflicts with.
+    pub existing_path: String,
+    /// Where in the code the existing route was added.
+    pub existing_location: &'static Location<'static>
+}
+
+impl std::fmt::Display for RouteConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "The route path '{}' conflicts with the existing route path '{}' for {} requests (added at {} and {} respectively)",
+            self.path, self.existing_path, self.method, self.location, self.existing_location)
+    }
+}
+
+impl std::error::Error for RouteConflict {}
+
+// An empty response with an `Allow` header listing the methods given.
+fn allow_response(status: StatusCode, allowed: &[Method]) -> Response<Vec<u8>> {
+    let allow: Vec<&str> = allowed.iter().map(|m| m.as_str()).collect();
+    Response::builder()
+        .status(status)
+        .header(header::ALLOW, allow.join(", "))
+        .body(vec![])
+        .expect("valid response")
+}
+
+// Add some headers to a response. `Vary` may already be set, so we append to it.
+fn add_headers(headers: &mut HeaderMap, new_headers: HeaderMap) {
+    for (name, value) in &new_headers {
+        if name == header::VARY {
+            headers.append(name, value.clone());
+        } else {
+            headers.insert(name, value.clone());
+        }
+    }
+}
+
+// Responses to HEAD requests have no body, but otherwise look like the
+// response to the corresponding GET request.
+fn without_body(res: Response<Vec<u8>>) -> Response<Vec<u8>> {
+    let (mut parts, body) = res.into_parts();
+    parts.headers.entry(header::CONTENT_LENGTH).or_insert_with(|| body.len().into());
+    Response::from_parts(parts, vec![])
+}
+
+/// Information about the routes in an API, along with the definitions of any named types
+/// that they refer to. This is handed back from [`Api::info_with_definitions()`].
+#[derive(Debug,Clone,PartialEq,Serialize)]
+pub struct ApiInfo {
+    /// Information about each of the routes.
+    pub routes: Vec<RouteInfo>,
+    /// The definition of each named type, keyed by the name given in the
+    /// [`crate::ApiBodyType::Ref`]s that refer to it.
+    pub definitions: BTreeMap<String, ApiBodyInfo>,
+    /// Information about the responses that errors are handed back in.
+    pub error_response: ErrorResponseInfo
+}
+
+/// Information about the responses that errors are handed back in. This is given by the
+/// [`ErrorRenderer`] handed to [`Api::render_errors()`], or otherwise describes the responses
+/// given back by [`ApiError::into_response()`].
+#[derive(Debug,Clone,PartialEq,Serialize)]
+pub struct ErrorResponseInfo {
+    /// The media type of error responses.
+    pub content_type: String,
+    /// The shape of the body of error responses.
+    pub body: ApiBodyInfo
 }
 
 /// Information about a single route.
@@ -215,6 +1169,29 @@ pub struct RouteInfo {
     pub method: String,
     /// The description of the route as set by [`RouteBuilder::description()`]
     pub description: String,
+    /// The summary of the route as set by [`RouteBuilder::summary()`].
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub summary: String,
+    /// The tags given to the route using [`RouteBuilder::tags()`].
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// If the route is deprecated (see [`RouteBuilder::deprecated()`]), this is the
+    /// message explaining what to use instead.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deprecated: Option<String>,
+    /// A unique identifier for the route, which documentation and generated clients can
+    /// use to refer to it. This is the identifier given using [`RouteBuilder::operation_id()`]
+    /// if there is one. Otherwise, it's based on the route path (for instance `usersList` for
+    /// `users/list`), with the method appended if other routes share the same path (for instance
+    /// `usersGet` and `usersPost`), and a number appended if necessary to keep it unique.
+    pub operation_id: String,
+    /// Any metadata attached to the route using [`RouteBuilder::meta()`].
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub meta: BTreeMap<String, serde_json::Value>,
+    /// The version of the API that this route handles requests for, if it was given
+    /// one using [`RouteBuilder::version()`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
     /// The shape of the data expected to be provided as part of the [`http::Request`]
     /// for this route. This doesn't care about the wire format that the data is provided in,
     /// though the type information is somewhat related to what the possible types that can
@@ -224,5 +1201,56 @@ pub struct RouteInfo {
     /// manually in order to describe the shape and documentation that they should hand back.
     pub request_type: ApiBodyInfo,
     /// The shape of the data that is returned from this API route.
-    pub response_type: ApiBodyInfo
+    pub response_type: ApiBodyInfo,
+    /// The errors that the handler of this route might hand back (see [`crate::api::ApiErrors`]).
+    /// Errors which occur before the handler is run (for instance because the request body is
+    /// invalid) are not included.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<ApiErrorInfo>,
+    /// The parameters in the route path (see [`crate::handler::param::Path`]), in the order
+    /// that they appear in it.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub path_params: Vec<PathParamInfo>,
+    /// The shape of the parameters expected in the query string (see [`crate::handler::param::Query`]),
+    /// if the route expects any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub query_type: Option<ApiBodyInfo>,
+    /// The headers that the route expects (see [`crate::handler::header`]).
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub headers: Vec<HeaderParamInfo>,
+    /// The groups (see [`Api::group()`]) that the route was added in, outermost first.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<RouteGroupInfo>
+}
+
+/// Information about a group of routes.
+#[derive(Debug,Clone,PartialEq,Serialize)]
+pub struct RouteGroupInfo {
+    /// The name of the group, which is also the prefix given to the paths of the routes in it
+    /// (relative to the prefix of any enclosing group).
+    pub name: String,
+    /// The description of the group as set by [`RouteGroup::description()`].
+    pub description: String
+}
+
+/// Information about a single parameter in a route path.
+#[derive(Debug,Clone,PartialEq,Serialize)]
+pub struct PathParamInfo {
+    /// The name of the parameter; for instance `id` given the route path `users/{id}`.
+    pub name: String,
+    /// The shape of the value expected in place of the parameter.
+    #[serde(rename = "type")]
+    pub ty: ApiBodyInfo
+}
+
+/// Information about a header that a route expects.
+#[derive(Debug,Clone,PartialEq,Serialize)]
+pub struct HeaderParamInfo {
+    /// The name of the header, in lowercase.
+    pub name: String,
+    /// Whether the request fails if the header isn't provided.
+    pub required: bool,
+    /// The shape of the value expected in the header.
+    #[serde(rename = "type")]
+    pub ty: ApiBodyInfo
 }
\ No newline at end of file
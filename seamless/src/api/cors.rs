@@ -0,0 +1,198 @@
+use std::time::Duration;
+use http::{ HeaderMap, HeaderValue, header, method::Method };
+
+/// Configure how an [`crate::Api`] responds to cross-origin requests from browsers. Set
+/// this for every route using [`crate::Api::cors()`], or for individual routes using
+/// [`crate::api::RouteBuilder::cors()`].
+///
+/// When this is set, preflight (`OPTIONS`) requests for matching routes are answered
+/// automatically, and the relevant `Access-Control-*` headers are added to successful
+/// responses. By default, no origins are allowed.
+///
+/// # Example
+///
+/// ```
+/// # use seamless::{ Api, api::CorsConfig, handler::{ body::FromJson, response::ToJson } };
+/// # use std::time::Duration;
+/// let mut api = Api::new();
+/// api.cors(
+///     CorsConfig::new()
+///         .allow_origin("https://example.com")
+///         .allow_headers(["content-type", "authorization"])
+///         .allow_credentials(true)
+///         .max_age(Duration::from_secs(3600))
+/// );
+///
+/// api.add("echo")
+///    .handler(|body: FromJson<String>| ToJson(body.0));
+/// ```
+#[derive(Debug,Clone,PartialEq,Default)]
+pub struct CorsConfig {
+    origins: Allowed<String>,
+    methods: Option<Vec<Method>>,
+    headers: Allowed<String>,
+    expose_headers: Vec<String>,
+    credentials: bool,
+    max_age: Option<Duration>
+}
+
+#[derive(Debug,Clone,PartialEq)]
+enum Allowed<T> {
+    Any,
+    Only(Vec<T>)
+}
+
+impl <T> Default for Allowed<T> {
+    fn default() -> Self {
+        Allowed::Only(vec![])
+    }
+}
+
+impl CorsConfig {
+
+    /// Create a new configuration, which allows no origins until some are added.
+    pub fn new() -> CorsConfig {
+        CorsConfig::default()
+    }
+
+    /// Allow requests from the origin provided (for instance `"https://example.com"`).
+    /// This can be called multiple times to allow several origins.
+    pub fn allow_origin<S: Into<String>>(mut self, origin: S) -> Self {
+        if let Allowed::Only(origins) = &mut self.origins {
+            origins.push(origin.into());
+        }
+        self
+    }
+
+    /// Allow requests from any origin.
+    pub fn allow_any_origin(mut self) -> Self {
+        self.origins = Allowed::Any;
+        self
+    }
+
+    /// Set the methods that are allowed. By default, the method of the route that
+    /// a request would be handled by is allowed.
+    pub fn allow_methods<I: IntoIterator<Item = Method>>(mut self, methods: I) -> Self {
+        self.methods = Some(methods.into_iter().collect());
+        self
+    }
+
+    /// Allow the request headers provided to be sent. Headers which are always allowed
+    /// by browsers don't need to be listed, but note that `Content-Type` is only one of these
+    /// for a few specific values (which don't include `application/json`).
+    pub fn allow_headers<S: Into<String>, I: IntoIterator<Item = S>>(mut self, headers: I) -> Self {
+        if let Allowed::Only(allowed) = &mut self.headers {
+            allowed.extend(headers.into_iter().map(|h| h.into().to_ascii_lowercase()));
+        }
+        self
+    }
+
+    /// Allow any request headers to be sent.
+    pub fn allow_any_header(mut self) -> Self {
+        self.headers = Allowed::Any;
+        self
+    }
+
+    /// Allow the response headers provided to be read by the browser.
+    pub fn expose_headers<S: Into<String>, I: IntoIterator<Item = S>>(mut self, headers: I) -> Self {
+        self.expose_headers.extend(headers.into_iter().map(|h| h.into()));
+        self
+    }
+
+    /// Allow requests to include credentials (like cookies).
+    pub fn allow_credentials(mut self, allow: bool) -> Self {
+        self.credentials = allow;
+        self
+    }
+
+    /// How long browsers can cache the response to a preflight request for.
+    pub fn max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    fn allows_origin(&self, origin: &str) -> bool {
+        match &self.origins {
+            Allowed::Any => true,
+            Allowed::Only(origins) => origins.iter().any(|o| o == origin)
+        }
+    }
+
+    /// The headers to respond to a preflight request with, or `None` if the request
+    /// is not allowed. `route_method` is the method of the route that would handle the
+    /// `requested_method` (these differ if a `GET` route handles a `HEAD` request).
+    pub (crate) fn preflight_headers(&self, req_headers: &HeaderMap, requested_method: &Method, route_method: &Method) -> Option<HeaderMap> {
+        let origin = req_headers.get(header::ORIGIN)?;
+        let mut headers = self.origin_headers(origin)?;
+
+        let methods = match &self.methods {
+            Some(methods) => methods.clone(),
+            None if route_method == Method::GET => vec![Method::GET, Method::HEAD],
+            None => vec![route_method.clone()]
+        };
+        if !methods.contains(requested_method) {
+            return None
+        }
+        headers.insert(header::ACCESS_CONTROL_ALLOW_METHODS, join(methods.iter().map(|m| m.as_str()))?);
+
+        // Check that each of the headers that the request wants to send is allowed:
+        let requested_headers = req_headers.get_all(header::ACCESS_CONTROL_REQUEST_HEADERS)
+            .iter()
+            .filter_map(|h| h.to_str().ok())
+            .flat_map(|h| h.split(','))
+            .map(|h| h.trim().to_ascii_lowercase())
+            .filter(|h| !h.is_empty());
+        let mut allowed_headers = vec![];
+        for requested in requested_headers {
+            match &self.headers {
+                Allowed::Only(allowed) if !allowed.contains(&requested) => return None,
+                _ => allowed_headers.push(requested)
+            }
+        }
+        if !allowed_headers.is_empty() {
+            headers.insert(header::ACCESS_CONTROL_ALLOW_HEADERS, join(allowed_headers.iter().map(|h| h.as_str()))?);
+        }
+
+        if let Some(max_age) = self.max_age {
+            headers.insert(header::ACCESS_CONTROL_MAX_AGE, max_age.as_secs().into());
+        }
+        Some(headers)
+    }
+
+    /// The headers to add to the response to a (non preflight) request. Nothing
+    /// is added if the origin of the request is not allowed.
+    pub (crate) fn response_headers(&self, req_headers: &HeaderMap) -> HeaderMap {
+        let mut headers = req_headers.get(header::ORIGIN)
+            .and_then(|origin| self.origin_headers(origin))
+            .unwrap_or_default();
+        if !headers.is_empty() && !self.expose_headers.is_empty() {
+            if let Some(expose) = join(self.expose_headers.iter().map(|h| h.as_str())) {
+                headers.insert(header::ACCESS_CONTROL_EXPOSE_HEADERS, expose);
+            }
+        }
+        headers
+    }
+
+    // The headers common to preflight and other responses, if the origin is allowed.
+    fn origin_headers(&self, origin: &HeaderValue) -> Option<HeaderMap> {
+        if !self.allows_origin(origin.to_str().ok()?) {
+            return None
+        }
+        let mut headers = HeaderMap::new();
+        // "*" isn't allowed alongside credentials, so we echo the origin back in that case:
+        if self.origins == Allowed::Any && !self.credentials {
+            headers.insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, HeaderValue::from_static("*"));
+        } else {
+            headers.insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, origin.clone());
+            headers.insert(header::VARY, HeaderValue::from_static("Origin"));
+        }
+        if self.credentials {
+            headers.insert(header::ACCESS_CONTROL_ALLOW_CREDENTIALS, HeaderValue::from_static("true"));
+        }
+        Some(headers)
+    }
+}
+
+fn join<'a>(values: impl Iterator<Item = &'a str>) -> Option<HeaderValue> {
+    HeaderValue::from_str(&values.collect::<Vec<_>>().join(", ")).ok()
+}
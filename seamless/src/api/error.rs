@@ -1,3 +1,8 @@
+use std::collections::HashMap;
+use http::{ Response, StatusCode, header };
+use serde::{ Serialize };
+use super::info::{ ApiBodyInfo, ApiBodyType };
+
 /// This represents an API error that is returned from the API.
 #[derive(Debug,Clone,PartialEq)]
 pub struct ApiError {
@@ -9,7 +14,14 @@ pub struct ApiError {
     pub external_message: String,
     /// Some optional context which could contain arbitrary information. It's expected that
     /// this could be handed back to API consumers and so shouldn't contain anything sensitive.
-    pub value: Option<serde_json::Value>
+    pub value: Option<serde_json::Value>,
+    /// An optional URI identifying the type of problem that this error represents. This is
+    /// handed back as the `type` of [RFC 7807](https://www.rfc-editor.org/rfc/rfc7807) problem
+    /// details (see [`crate::api::ProblemDetails`]).
+    pub problem_type: Option<String>,
+    /// An optional, stable, machine readable code identifying the error (eg `"maths.divide_by_zero"`).
+    /// Unlike the `external_message`, API consumers can rely on this not changing.
+    pub error_code: Option<String>
 }
 
 impl ApiError {
@@ -24,7 +36,9 @@ impl ApiError {
             code: 500,
             internal_message: msg.into(),
             external_message: ApiError::SERVER_ERROR.to_owned(),
-            value: None
+            value: None,
+            problem_type: None,
+            error_code: None
         }
     }
 
@@ -34,7 +48,9 @@ impl ApiError {
             code: 404,
             internal_message: "Not found".to_owned(),
             external_message: "Not found".to_owned(),
-            value: None
+            value: None,
+            problem_type: None,
+            error_code: None
         }
     }
 
@@ -45,11 +61,209 @@ impl ApiError {
             code: 403,
             external_message: msg.clone(),
             internal_message: msg,
-            value: None
+            value: None,
+            problem_type: None,
+            error_code: None
+        }
+    }
+
+    /// Convert this error into an HTTP response to hand back to API consumers. The status
+    /// code of the response is given by [`ApiError::code`] (falling back to `500` if it's not
+    /// valid), and the body is JSON containing the `code`, `error_code`, `external_message` and
+    /// `value` of the error (see [`ApiError::response_body_info()`]). The `internal_message` is never
+    /// included.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use seamless::ApiError;
+    /// # use serde_json::json;
+    /// let res = ApiError::server_error("database is down").into_response();
+    /// assert_eq!(res.status(), 500);
+    /// assert_eq!(res.headers()["content-type"], "application/json");
+    ///
+    /// let body: serde_json::Value = serde_json::from_slice(res.body()).unwrap();
+    /// assert_eq!(body, json!({
+    ///     "code": 500,
+    ///     "error_code": null,
+    ///     "external_message": "Internal server error",
+    ///     "value": null
+    /// }));
+    /// ```
+    pub fn into_response(self) -> Response<Vec<u8>> {
+        let status = StatusCode::from_u16(self.code).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        let body = ApiErrorBody {
+            code: status.as_u16(),
+            error_code: &self.error_code,
+            external_message: &self.external_message,
+            value: &self.value
+        };
+        Response::builder()
+            .status(status)
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(serde_json::to_vec(&body).expect("error body should serialize"))
+            .expect("valid response")
+    }
+
+    /// A description of the JSON body of the responses handed back by [`ApiError::into_response()`],
+    /// so that clients can know what shape errors will take.
+    pub fn response_body_info() -> ApiBodyInfo {
+        let key = |description: &str, ty: ApiBodyType| ApiBodyInfo { description: description.to_owned(), ty };
+        let keys: HashMap<String, ApiBodyInfo> = vec![
+            ("code", key("The HTTP status code", ApiBodyType::Number)),
+            ("error_code", key("A code identifying the error, or null if there is none", ApiBodyType::OneOf {
+                values: vec![key("", ApiBodyType::String), key("", ApiBodyType::Null)]
+            })),
+            ("external_message", key("A message describing the error", ApiBodyType::String)),
+            ("value", key("Additional context about the error, or null if there is none", ApiBodyType::Any))
+        ].into_iter().map(|(name, info)| (name.to_owned(), info)).collect();
+        ApiBodyInfo {
+            description: "An error returned from the API".to_owned(),
+            ty: ApiBodyType::Object { keys }
         }
     }
 }
 
+// The JSON body of the responses that `ApiError::into_response()` hands back. Keep
+// this in sync with `ApiError::response_body_info()`.
+#[derive(Serialize)]
+struct ApiErrorBody<'a> {
+    code: u16,
+    error_code: &'a Option<String>,
+    external_message: &'a str,
+    value: &'a Option<serde_json::Value>
+}
+
 impl From<std::convert::Infallible> for ApiError {
     fn from(_: std::convert::Infallible) -> ApiError { unreachable!() }
 }
+
+// Serialize something to hand back as the `value` of an `ApiError`. This is used by the
+// `ApiError` macro for fields marked with `#[api_error(value)]`.
+#[doc(hidden)]
+pub fn error_value<T: Serialize + ?Sized>(value: &T) -> Option<serde_json::Value> {
+    serde_json::to_value(value).ok()
+}
+
+// Hand back an object containing each of the named values provided as the `value` of an
+// `ApiError`. This is used by the `ApiError` macro for structs and variants with named fields.
+#[doc(hidden)]
+pub fn error_value_object(values: Vec<(&str, Option<serde_json::Value>)>) -> Option<serde_json::Value> {
+    let members = values.into_iter()
+        .map(|(name, value)| (name.to_owned(), value.unwrap_or(serde_json::Value::Null)))
+        .collect();
+    Some(serde_json::Value::Object(members))
+}
+
+// Describe an object containing each of the named values provided. This is used by the `ApiError`
+// macro for structs and variants with named fields.
+#[doc(hidden)]
+pub fn error_value_object_info(keys: Vec<(&str, ApiBodyInfo)>) -> ApiBodyInfo {
+    ApiBodyInfo {
+        description: String::new(),
+        ty: ApiBodyType::Object {
+            keys: keys.into_iter().map(|(name, info)| (name.to_owned(), info)).collect()
+        }
+    }
+}
+
+/// A description of some [`ApiError`] that a route might hand back. Routes are described as
+/// handing back these errors in [`crate::api::RouteInfo::errors`].
+#[derive(Debug,Clone,PartialEq,Serialize)]
+pub struct ApiErrorInfo {
+    /// The HTTP status code of the error.
+    pub code: u16,
+    /// The external message of the error, if it's always the same. This is `None` if it depends
+    /// on the details of the error (for instance if the message is taken from its `Display` impl).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub external_message: Option<String>,
+    /// The shape of the value handed back with the error, if there is one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<ApiBodyInfo>,
+    /// The problem type of the error, if it has one (see [`ApiError::problem_type`]).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub problem_type: Option<String>,
+    /// The error code of the error, if it has one (see [`ApiError::error_code`]).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_code: Option<String>
+}
+
+/// Errors returned from handlers implement this in order to describe the [`ApiError`]s
+/// that they can be converted into, so that the routes returning them can advertise which
+/// errors they might hand back. This is implemented automatically by the
+/// [`ApiError`](seamless_macros::ApiError) macro.
+///
+/// If you implement `Into<ApiError>` manually, you'll need to implement this too. By
+/// default, no errors are described, so an empty `impl ApiErrors for MyError {}` will do.
+///
+/// # Example
+///
+/// ```
+/// # use seamless::api::{ ApiError, ApiErrors, ApiErrorInfo };
+/// struct NotLoggedIn;
+///
+/// impl From<NotLoggedIn> for ApiError {
+///     fn from(_: NotLoggedIn) -> ApiError {
+///         ApiError::not_authorized("not logged in")
+///     }
+/// }
+///
+/// impl ApiErrors for NotLoggedIn {
+///     fn api_error_info() -> Vec<ApiErrorInfo> {
+///         vec![ApiErrorInfo {
+///             code: 403,
+///             external_message: Some("Not Authorized: not logged in".to_owned()),
+///             value: None,
+///             problem_type: None,
+///             error_code: None
+///         }]
+///     }
+/// }
+/// ```
+pub trait ApiErrors {
+    /// Describe each of the errors that this can be converted into.
+    fn api_error_info() -> Vec<ApiErrorInfo> {
+        vec![]
+    }
+}
+
+// Add some errors to a list of errors, skipping any that are already in it.
+pub (crate) fn merge_errors(mut errors: Vec<ApiErrorInfo>, new_errors: Vec<ApiErrorInfo>) -> Vec<ApiErrorInfo> {
+    for error in new_errors {
+        if !errors.contains(&error) {
+            errors.push(error);
+        }
+    }
+    errors
+}
+
+// The ApiError macro uses these to describe the errors that an inner type can be
+// converted into. Method resolution picks `ViaApiErrors` if the inner type implements
+// `ApiErrors`, and otherwise falls back to `ViaFallback`, which describes nothing:
+#[doc(hidden)]
+pub struct ApiErrorsProbe<T>(pub std::marker::PhantomData<T>);
+
+#[doc(hidden)]
+pub trait ViaApiErrors {
+    fn api_error_info(&self) -> Vec<ApiErrorInfo>;
+}
+impl <T: ApiErrors> ViaApiErrors for ApiErrorsProbe<T> {
+    fn api_error_info(&self) -> Vec<ApiErrorInfo> {
+        T::api_error_info()
+    }
+}
+
+#[doc(hidden)]
+pub trait ViaFallback {
+    fn api_error_info(&self) -> Vec<ApiErrorInfo>;
+}
+impl <T> ViaFallback for &ApiErrorsProbe<T> {
+    fn api_error_info(&self) -> Vec<ApiErrorInfo> {
+        vec![]
+    }
+}
+
+// We can't know which errors an arbitrary `ApiError` will be:
+impl ApiErrors for ApiError {}
+
+impl ApiErrors for std::convert::Infallible {}
@@ -1,4 +1,6 @@
-use std::collections::HashMap;
+use std::collections::{ BTreeMap, HashMap };
+use serde_json::json;
+use super::registry::TypeRegistry;
 
 pub use serde::{ Serialize, Deserialize };
 
@@ -28,6 +30,160 @@ pub struct ApiBodyStructInfo {
     pub struc: HashMap<String, ApiBodyInfo>
 }
 
+impl ApiBodyInfo {
+    /// Convert this into a standalone [JSON Schema](https://json-schema.org/draft/2020-12/schema)
+    /// document (draft 2020-12) which can be used to validate JSON values of this shape.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use seamless::ApiBody;
+    /// # use serde_json::json;
+    /// /// Some user
+    /// #[ApiBody]
+    /// struct User {
+    ///     name: String,
+    ///     age: Option<usize>
+    /// }
+    ///
+    /// assert_eq!(
+    ///     User::api_body_info().to_json_schema(),
+    ///     json!({
+    ///         "$schema": "https://json-schema.org/draft/2020-12/schema",
+    ///         "description": "Some user",
+    ///         "type": "object",
+    ///         "properties": {
+    ///             "name": { "type": "string" },
+    ///             "age": { "anyOf": [{ "type": "number" }, { "type": "null" }] }
+    ///         },
+    ///         "required": ["name"]
+    ///     })
+    /// );
+    /// ```
+    pub fn to_json_schema(&self) -> serde_json::Value {
+        self.to_json_schema_with_definitions(&BTreeMap::new())
+    }
+
+    /// Like [`ApiBodyInfo::to_json_schema()`], except that the definitions provided are
+    /// included in the schema under `$defs`, so that any [`ApiBodyType::Ref`]s pointing to
+    /// them can be resolved. This is necessary to describe recursive types.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use seamless::ApiBody;
+    /// # use serde_json::json;
+    /// #[ApiBody]
+    /// struct TreeNode {
+    ///     children: Vec<TreeNode>
+    /// }
+    ///
+    /// let (info, definitions) = TreeNode::api_body_info_with_definitions();
+    ///
+    /// assert_eq!(
+    ///     info.to_json_schema_with_definitions(&definitions),
+    ///     json!({
+    ///         "$schema": "https://json-schema.org/draft/2020-12/schema",
+    ///         "$ref": "#/$defs/TreeNode",
+    ///         "$defs": {
+    ///             "TreeNode": {
+    ///                 "type": "object",
+    ///                 "properties": {
+    ///                     "children": {
+    ///                         "type": "array",
+    ///                         "items": { "$ref": "#/$defs/TreeNode" }
+    ///                     }
+    ///                 },
+    ///                 "required": ["children"]
+    ///             }
+    ///         }
+    ///     })
+    /// );
+    /// ```
+    pub fn to_json_schema_with_definitions(&self, definitions: &BTreeMap<String, ApiBodyInfo>) -> serde_json::Value {
+        let mut schema = self.json_schema("#/$defs/");
+        schema["$schema"] = json!("https://json-schema.org/draft/2020-12/schema");
+        if !definitions.is_empty() {
+            let defs: serde_json::Map<_,_> = definitions.iter()
+                .map(|(name, def)| (name.clone(), def.json_schema("#/$defs/")))
+                .collect();
+            schema["$defs"] = serde_json::Value::Object(defs);
+        }
+        schema
+    }
+
+    // The JSON Schema for this type, without the `$schema` property, so that it
+    // can be embedded in other documents. References to named types point to
+    // `ref_prefix` followed by the name of the type.
+    pub (crate) fn json_schema(&self, ref_prefix: &str) -> serde_json::Value {
+        let schema = match &self.ty {
+            ApiBodyType::String => json!({ "type": "string" }),
+            ApiBodyType::Number => json!({ "type": "number" }),
+            ApiBodyType::Boolean => json!({ "type": "boolean" }),
+            ApiBodyType::Null => json!({ "type": "null" }),
+            ApiBodyType::Any => json!({}),
+            ApiBodyType::Unknown => json!({}),
+            ApiBodyType::Binary => json!({
+                "type": "string",
+                "contentMediaType": "application/octet-stream"
+            }),
+            ApiBodyType::ArrayOf { value } => json!({
+                "type": "array",
+                "items": value.json_schema(ref_prefix)
+            }),
+            ApiBodyType::TupleOf { values } => json!({
+                "type": "array",
+                "prefixItems": values.iter().map(|v| v.json_schema(ref_prefix)).collect::<Vec<_>>(),
+                "minItems": values.len(),
+                "maxItems": values.len()
+            }),
+            ApiBodyType::ObjectOf { value } => json!({
+                "type": "object",
+                "additionalProperties": value.json_schema(ref_prefix)
+            }),
+            ApiBodyType::Object { keys } => {
+                let mut properties = serde_json::Map::new();
+                let mut required = vec![];
+                for (key, value) in keys {
+                    if !matches!(value.ty, ApiBodyType::Optional{..}) {
+                        required.push(key.clone());
+                    }
+                    properties.insert(key.clone(), value.json_schema(ref_prefix));
+                }
+                required.sort();
+                json!({
+                    "type": "object",
+                    "properties": properties,
+                    "required": required
+                })
+            },
+            ApiBodyType::OneOf { values } => json!({
+                "oneOf": values.iter().map(|v| v.json_schema(ref_prefix)).collect::<Vec<_>>()
+            }),
+            ApiBodyType::StringLiteral { literal } => json!({
+                "type": "string",
+                "const": literal
+            }),
+            // Optional values serialize to null when they aren't present:
+            ApiBodyType::Optional { value } => json!({
+                "anyOf": [value.json_schema(ref_prefix), { "type": "null" }]
+            }),
+            ApiBodyType::Ref { name } => json!({
+                "$ref": format!("{}{}", ref_prefix, name)
+            })
+        };
+        self.describe_json_schema(schema)
+    }
+
+    // Add our description, if we have one, to the schema provided.
+    pub (crate) fn describe_json_schema(&self, mut schema: serde_json::Value) -> serde_json::Value {
+        if !self.description.is_empty() {
+            schema["description"] = json!(self.description);
+        }
+        schema
+    }
+}
+
 /// An enum representing the shape of the JSON that is provided or output from the API.
 /// There is a straightforward mapping from this to TypeScript types.
 #[derive(Debug,Clone,PartialEq,Eq,Serialize)]
@@ -97,6 +253,13 @@ pub enum ApiBodyType {
     Optional {
         /// The type that is optional.
         value: Box<ApiBodyInfo>
+    },
+    /// A reference to a named type (one that uses the [`ApiBody`](seamless_macros::ApiBody)
+    /// macro), eg `Foo`. The definitions that these refer to are handed back alongside
+    /// the routes in [`crate::Api::info_with_definitions()`].
+    Ref {
+        /// The name of the type that is being referred to.
+        name: String
     }
 }
 
@@ -110,8 +273,22 @@ pub enum ApiBodyType {
 /// In some cases however, it is necessary to manually implement this for a type (for example, an external type).
 pub trait ApiBody {
     /// This returns information about the shape of the type and description of parts of it.
+    ///
+    /// Types using the [`ApiBody`](seamless_macros::ApiBody) macro are described inline, with
+    /// the exception of recursive types: when such a type is encountered again while describing
+    /// itself, an [`ApiBodyType::Ref`] to it is returned instead.
     fn api_body_info() -> ApiBodyInfo;
 
+    /// Like [`ApiBody::api_body_info()`], except that every named type (ie those using the
+    /// [`ApiBody`](seamless_macros::ApiBody) macro) is described just once in the definitions
+    /// handed back, and referred to everywhere else by name via [`ApiBodyType::Ref`].
+    fn api_body_info_with_definitions() -> (ApiBodyInfo, BTreeMap<String, ApiBodyInfo>)
+    where Self: Sized {
+        let mut registry = TypeRegistry::default();
+        let info = registry.collect(Self::api_body_info);
+        (info, registry.into_definitions())
+    }
+
     /// Serialize the type to JSON.
     fn to_json_vec(&self) -> Vec<u8>
     where Self: ::serde::Serialize {
@@ -0,0 +1,109 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use http::{ Request, Response };
+use super::error::ApiError;
+use crate::handler::request::AsyncReadBody;
+
+/// The future returned from [`Middleware::handle()`] and [`Next::run()`].
+pub type MiddlewareFuture<'a> = Pin<Box<dyn Future<Output = Result<Response<Vec<u8>>,ApiError>> + Send + 'a>>;
+
+// The boxed handler function that middleware eventually hands requests to.
+pub (crate) type HandlerFn = dyn for<'a> Fn(Request<&'a mut dyn AsyncReadBody>) -> MiddlewareFuture<'a> + Send + Sync;
+
+/// Implement this in order to run code around route handlers. Middleware is given each
+/// request that it applies to along with a [`Next`] continuation, which runs the remaining
+/// middleware and then the handler itself. Middleware can modify the request before calling
+/// [`Next::run()`], modify the response after it, or return an [`ApiError`] without calling it
+/// at all in order to stop the request from going any further.
+///
+/// Add middleware to every route using [`crate::Api::wrap()`], or to individual routes
+/// using [`crate::api::RouteBuilder::wrap()`]. Closures can be used as middleware by
+/// handing them to [`middleware_fn()`].
+///
+/// # Example
+///
+/// ```
+/// # use seamless::{ Api, api::{ ApiError, Middleware, MiddlewareFuture, Next }, handler::request::AsyncReadBody };
+/// # use seamless::http::Request;
+/// // Reject any request that doesn't have an "authorization" header:
+/// struct RequireAuth;
+///
+/// impl Middleware for RequireAuth {
+///     fn handle<'a>(&'a self, req: Request<&'a mut dyn AsyncReadBody>, next: Next<'a>) -> MiddlewareFuture<'a> {
+///         Box::pin(async move {
+///             if !req.headers().contains_key("authorization") {
+///                 return Err(ApiError::not_authorized("No authorization header"))
+///             }
+///             next.run(req).await
+///         })
+///     }
+/// }
+///
+/// let mut api = Api::new();
+/// api.wrap(RequireAuth);
+/// ```
+pub trait Middleware: Send + Sync + 'static {
+    /// Handle a request, calling [`Next::run()`] in order to pass it on to
+    /// the rest of the middleware and the route handler.
+    fn handle<'a>(&'a self, req: Request<&'a mut dyn AsyncReadBody>, next: Next<'a>) -> MiddlewareFuture<'a>;
+}
+
+/// Use a function or closure as [`Middleware`].
+///
+/// # Example
+///
+/// ```
+/// # use seamless::{ Api, api::middleware_fn };
+/// let mut api = Api::new();
+///
+/// // Add a header to every response:
+/// api.wrap(middleware_fn(|req, next| Box::pin(async move {
+///     let mut res = next.run(req).await?;
+///     res.headers_mut().insert("x-powered-by", "seamless".parse().unwrap());
+///     Ok(res)
+/// })));
+/// ```
+pub fn middleware_fn<F>(f: F) -> impl Middleware
+where F: for<'a> Fn(Request<&'a mut dyn AsyncReadBody>, Next<'a>) -> MiddlewareFuture<'a> + Send + Sync + 'static
+{
+    MiddlewareFn(f)
+}
+
+struct MiddlewareFn<F>(F);
+
+impl <F> Middleware for MiddlewareFn<F>
+where F: for<'a> Fn(Request<&'a mut dyn AsyncReadBody>, Next<'a>) -> MiddlewareFuture<'a> + Send + Sync + 'static
+{
+    fn handle<'a>(&'a self, req: Request<&'a mut dyn AsyncReadBody>, next: Next<'a>) -> MiddlewareFuture<'a> {
+        (self.0)(req, next)
+    }
+}
+
+/// This is handed to [`Middleware`], and runs the rest of the middleware and then
+/// the route handler.
+pub struct Next<'a> {
+    // Middleware applied to every route runs before middleware applied to the route itself:
+    api_middleware: &'a [Arc<dyn Middleware>],
+    route_middleware: &'a [Arc<dyn Middleware>],
+    handler: &'a HandlerFn
+}
+
+impl <'a> Next<'a> {
+    pub (crate) fn new(api_middleware: &'a [Arc<dyn Middleware>], route_middleware: &'a [Arc<dyn Middleware>], handler: &'a HandlerFn) -> Next<'a> {
+        Next { api_middleware, route_middleware, handler }
+    }
+
+    /// Run the rest of the middleware and then the route handler, handing back the response.
+    pub fn run(mut self, req: Request<&'a mut dyn AsyncReadBody>) -> MiddlewareFuture<'a> {
+        if let Some((first, rest)) = self.api_middleware.split_first() {
+            self.api_middleware = rest;
+            first.handle(req, self)
+        } else if let Some((first, rest)) = self.route_middleware.split_first() {
+            self.route_middleware = rest;
+            first.handle(req, self)
+        } else {
+            (self.handler)(req)
+        }
+    }
+}
@@ -5,10 +5,26 @@
 mod api;
 mod info;
 mod error;
+mod registry;
+mod path;
+mod router;
+mod cors;
+mod middleware;
+mod version;
+mod render;
 
-pub use api::{ Api, RouteBuilder, RouteError, RouteInfo };
+pub use api::{ Api, ApiInfo, RouteBuilder, RouteGroup, RouteError, RouteConflict, RouteInfo, RouteGroupInfo, PathParamInfo, HeaderParamInfo, ErrorResponseInfo };
 pub use info::{ ApiBody, ApiBodyInfo, ApiBodyType };
-pub use error::{ ApiError };
+pub use error::{ ApiError, ApiErrorInfo, ApiErrors };
+pub use cors::{ CorsConfig };
+pub use middleware::{ Middleware, MiddlewareFuture, Next, middleware_fn };
+pub use version::{ VersionSelector, PathVersion, HeaderVersion, MediaTypeVersion };
+pub use render::{ ErrorRenderer, JsonErrors, ProblemDetails };
+
+// Handler params use this to decode query strings:
+pub (crate) use path::{ percent_decode };
+// Handler responses use this to combine the errors they describe:
+pub (crate) use error::{ merge_errors };
 
 // Export these on top of the types, so that you don't need to
 // import `seamless::api::ApiBody` AND `seamless::ApiBody` for
@@ -18,4 +34,8 @@ pub use seamless_macros::{ ApiBody, ApiError };
 // These are used in seamless_macros but are not expected to
 // be made use of elsewhere and so are hidden from the docs:
 #[doc(hidden)]
-pub use info::{ ApiBodyStruct, ApiBodyStructInfo };
\ No newline at end of file
+pub use info::{ ApiBodyStruct, ApiBodyStructInfo };
+#[doc(hidden)]
+pub use registry::{ named_api_body_info };
+#[doc(hidden)]
+pub use error::{ error_value, error_value_object, error_value_object_info, ApiErrorsProbe, ViaApiErrors, ViaFallback };
\ No newline at end of file
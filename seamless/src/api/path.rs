@@ -0,0 +1,111 @@
+use std::fmt;
+
+/// A route path, split into segments on `/`. Any segment of the form `{name}` is a
+/// parameter, and captures whatever is in the corresponding segment of a request path.
+/// The last segment can also be of the form `{*name}`, which captures the rest of the
+/// request path.
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub (crate) struct PathTemplate {
+    segments: Vec<Segment>
+}
+
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub (crate) enum Segment {
+    Static(String),
+    Param(String),
+    CatchAll(String)
+}
+
+impl PathTemplate {
+
+    /// Parse a route path (which is expected to have had leading and trailing
+    /// slashes removed already), complaining if it's not valid.
+    pub fn parse(path: &str) -> Result<PathTemplate, String> {
+        let mut segments: Vec<Segment> = vec![];
+        for segment in path.split('/') {
+            if let Some(Segment::CatchAll(name)) = segments.last() {
+                return Err(format!("The catch-all parameter '{}' must be the last segment of the path", name))
+            }
+            let segment = match segment.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+                Some(name) => {
+                    let (name, is_catch_all) = match name.strip_prefix('*') {
+                        Some(name) => (name, true),
+                        None => (name, false)
+                    };
+                    if name.is_empty() || name.contains(['{', '}', '*']) {
+                        return Err(format!("'{}' is not a valid path parameter", segment))
+                    }
+                    if segments.iter().any(|s| s.param_name() == Some(name)) {
+                        return Err(format!("The path parameter '{}' is used more than once", name))
+                    }
+                    if is_catch_all {
+                        Segment::CatchAll(name.to_owned())
+                    } else {
+                        Segment::Param(name.to_owned())
+                    }
+                },
+                None => {
+                    if segment.contains(['{', '}']) {
+                        return Err(format!("'{}' is not a valid path segment; parameters must make up an entire segment", segment))
+                    }
+                    Segment::Static(segment.to_owned())
+                }
+            };
+            segments.push(segment);
+        }
+        Ok(PathTemplate { segments })
+    }
+
+    /// The segments making up this path.
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    /// The names of each of the parameters in the path, in order.
+    pub fn param_names(&self) -> impl Iterator<Item = &str> {
+        self.segments.iter().filter_map(Segment::param_name)
+    }
+}
+
+impl Segment {
+    fn param_name(&self) -> Option<&str> {
+        match self {
+            Segment::Param(name) | Segment::CatchAll(name) => Some(name),
+            Segment::Static(_) => None
+        }
+    }
+}
+
+impl fmt::Display for PathTemplate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (idx, segment) in self.segments.iter().enumerate() {
+            if idx > 0 {
+                f.write_str("/")?;
+            }
+            match segment {
+                Segment::Static(s) => write!(f, "{}", s)?,
+                Segment::Param(name) => write!(f, "{{{}}}", name)?,
+                Segment::CatchAll(name) => write!(f, "{{*{}}}", name)?
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Decode any `%XX` escapes in some path segment (or query string component), returning `None` if they are
+/// invalid or don't decode to valid UTF-8.
+pub (crate) fn percent_decode(s: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(s.len());
+    let mut rest = s.as_bytes();
+    while let Some((&b, tail)) = rest.split_first() {
+        if b == b'%' {
+            let hex = tail.get(..2).filter(|h| h.iter().all(u8::is_ascii_hexdigit))?;
+            bytes.push(u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok()?);
+            rest = &tail[2..];
+        } else {
+            bytes.push(b);
+            rest = tail;
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
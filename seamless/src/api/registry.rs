@@ -0,0 +1,222 @@
+use std::any::TypeId;
+use std::cell::RefCell;
+use std::collections::{ BTreeMap, HashMap };
+use super::info::{ ApiBodyInfo, ApiBodyType };
+
+thread_local! {
+    // While a registry is collecting type information, it lives here so that
+    // `named_api_body_info` can get hold of it.
+    static COLLECTING: RefCell<Option<TypeRegistry>> = const { RefCell::new(None) };
+    // When not collecting, we keep track of the named types currently being
+    // described here, so that we can spot (and break) cycles.
+    static DESCRIBING: RefCell<Vec<TypeId>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Keeps track of the named types (ie those that use the [`macro@crate::ApiBody`] macro)
+/// that have been seen, so that each one is only described once and is otherwise
+/// referred to by name via [`ApiBodyType::Ref`].
+#[derive(Default)]
+pub (crate) struct TypeRegistry {
+    names: HashMap<TypeId, String>,
+    definitions: BTreeMap<String, ApiBodyInfo>
+}
+
+impl TypeRegistry {
+
+    /// Run the provided function. Any named types that are described while it runs
+    /// are added to this registry and replaced with [`ApiBodyType::Ref`]s.
+    pub fn collect<R>(&mut self, f: impl FnOnce() -> R) -> R {
+        // Put the registry back where it came from (and restore any registry that
+        // was previously collecting) even if `f` panics:
+        struct Restore<'a>(&'a mut TypeRegistry, Option<TypeRegistry>);
+        impl Drop for Restore<'_> {
+            fn drop(&mut self) {
+                let registry = COLLECTING.with(|c| c.replace(self.1.take()));
+                *self.0 = registry.unwrap_or_default();
+            }
+        }
+
+        let previous = COLLECTING.with(|c| c.replace(Some(std::mem::take(self))));
+        let _restore = Restore(self, previous);
+        f()
+    }
+
+    /// The definitions of each of the named types we've seen.
+    pub fn definitions(&self) -> &BTreeMap<String, ApiBodyInfo> {
+        &self.definitions
+    }
+
+    /// Hand back the definitions of each of the named types we've seen.
+    pub fn into_definitions(self) -> BTreeMap<String, ApiBodyInfo> {
+        self.definitions
+    }
+
+    /// Add the named types from another registry to this one. Types which are in both are
+    /// only described once, and types whose names are already taken by other types here
+    /// are given new names. The names that changed are handed back; see [`rename_refs()`].
+    pub fn merge(&mut self, other: TypeRegistry) -> HashMap<String, String> {
+        let mut renames = HashMap::new();
+        let mut added = vec![];
+        for (type_id, name) in other.names {
+            let new_name = match self.names.get(&type_id) {
+                Some(existing) => existing.clone(),
+                None => {
+                    let new_name = self.unique_name(&name);
+                    self.names.insert(type_id, new_name.clone());
+                    added.push((name.clone(), new_name.clone()));
+                    new_name
+                }
+            };
+            if new_name != name {
+                renames.insert(name, new_name);
+            }
+        }
+        // Definitions can refer to other types, which may have been renamed:
+        for (name, new_name) in added {
+            if let Some(definition) = other.definitions.get(&name) {
+                self.definitions.insert(new_name, rename_refs(definition, &renames));
+            }
+        }
+        renames
+    }
+
+    /// Replace each [`ApiBodyType::Ref`] in the provided type with the definition that
+    /// it refers to. References to a type from within its own definition are left as-is,
+    /// since they can't be expanded.
+    pub fn resolve(&self, info: &ApiBodyInfo) -> ApiBodyInfo {
+        self.resolve_inner(info, &mut vec![])
+    }
+
+    fn resolve_inner(&self, info: &ApiBodyInfo, seen: &mut Vec<String>) -> ApiBodyInfo {
+        let resolve_box = |value: &ApiBodyInfo, seen: &mut Vec<String>| Box::new(self.resolve_inner(value, seen));
+        let resolve_vec = |values: &[ApiBodyInfo], seen: &mut Vec<String>| {
+            values.iter().map(|v| self.resolve_inner(v, seen)).collect()
+        };
+
+        let ty = match &info.ty {
+            ApiBodyType::Ref { name } => {
+                let definition = match self.definitions.get(name) {
+                    Some(d) if !seen.contains(name) => d,
+                    _ => return info.clone()
+                };
+                seen.push(name.clone());
+                let mut resolved = self.resolve_inner(definition, seen);
+                seen.pop();
+                // Descriptions given to the reference (eg field docs) take precedence:
+                if !info.description.is_empty() {
+                    resolved.description = info.description.clone();
+                }
+                return resolved
+            },
+            ApiBodyType::ArrayOf { value } => ApiBodyType::ArrayOf { value: resolve_box(value, seen) },
+            ApiBodyType::ObjectOf { value } => ApiBodyType::ObjectOf { value: resolve_box(value, seen) },
+            ApiBodyType::Optional { value } => ApiBodyType::Optional { value: resolve_box(value, seen) },
+            ApiBodyType::TupleOf { values } => ApiBodyType::TupleOf { values: resolve_vec(values, seen) },
+            ApiBodyType::OneOf { values } => ApiBodyType::OneOf { values: resolve_vec(values, seen) },
+            ApiBodyType::Object { keys } => ApiBodyType::Object {
+                keys: keys.iter().map(|(k, v)| (k.clone(), self.resolve_inner(v, seen))).collect()
+            },
+            ty => ty.clone()
+        };
+        ApiBodyInfo { description: info.description.clone(), ty }
+    }
+
+    // Names are unique; if two different types share a name, the latter is given a numeric suffix.
+    fn unique_name(&self, name: &str) -> String {
+        let is_taken = |n: &str| self.names.values().any(|taken| taken == n);
+        if !is_taken(name) {
+            return name.to_owned()
+        }
+        (2..).map(|n| format!("{}{}", name, n))
+            .find(|n| !is_taken(n))
+            .unwrap()
+    }
+}
+
+/// Rename any [`ApiBodyType::Ref`]s in the provided type whose names are in `renames`.
+pub (crate) fn rename_refs(info: &ApiBodyInfo, renames: &HashMap<String, String>) -> ApiBodyInfo {
+    let rename_box = |value: &ApiBodyInfo| Box::new(rename_refs(value, renames));
+    let rename_vec = |values: &[ApiBodyInfo]| values.iter().map(|v| rename_refs(v, renames)).collect();
+
+    let ty = match &info.ty {
+        ApiBodyType::Ref { name } => ApiBodyType::Ref {
+            name: renames.get(name).unwrap_or(name).clone()
+        },
+        ApiBodyType::ArrayOf { value } => ApiBodyType::ArrayOf { value: rename_box(value) },
+        ApiBodyType::ObjectOf { value } => ApiBodyType::ObjectOf { value: rename_box(value) },
+        ApiBodyType::Optional { value } => ApiBodyType::Optional { value: rename_box(value) },
+        ApiBodyType::TupleOf { values } => ApiBodyType::TupleOf { values: rename_vec(values) },
+        ApiBodyType::OneOf { values } => ApiBodyType::OneOf { values: rename_vec(values) },
+        ApiBodyType::Object { keys } => ApiBodyType::Object {
+            keys: keys.iter().map(|(k, v)| (k.clone(), rename_refs(v, renames))).collect()
+        },
+        ty => ty.clone()
+    };
+    ApiBodyInfo { description: info.description.clone(), ty }
+}
+
+/// This is used by the [`macro@crate::ApiBody`] macro to describe a named type. If a
+/// [`TypeRegistry`] is collecting types, the description is handed to that and a
+/// reference to it is returned instead. Otherwise, the type is described inline, except
+/// for recursive references to itself, which are returned as references.
+#[doc(hidden)]
+pub fn named_api_body_info<T: ?Sized + 'static>(name: &str, info: impl FnOnce() -> ApiBodyInfo) -> ApiBodyInfo {
+    let type_id = TypeId::of::<T>();
+
+    // Look up or reserve a name for this type. We don't hold the borrow while describing
+    // the type, since that may involve describing other named types.
+    let reserved = COLLECTING.with(|c| {
+        let mut c = c.borrow_mut();
+        let registry = c.as_mut()?;
+        if let Some(name) = registry.names.get(&type_id) {
+            return Some((name.clone(), true))
+        }
+        let name = registry.unique_name(name);
+        registry.names.insert(type_id, name.clone());
+        Some((name, false))
+    });
+
+    let name = match reserved {
+        // Not collecting types, so describe the type inline:
+        None => return describe_inline(type_id, name, info),
+        // We've seen (or are in the middle of describing) this type:
+        Some((name, true)) => return ref_to(name),
+        // A new type to describe:
+        Some((name, false)) => name
+    };
+
+    let definition = info();
+    COLLECTING.with(|c| {
+        if let Some(registry) = c.borrow_mut().as_mut() {
+            registry.definitions.insert(name.clone(), definition);
+        }
+    });
+    ref_to(name)
+}
+
+fn describe_inline(type_id: TypeId, name: &str, info: impl FnOnce() -> ApiBodyInfo) -> ApiBodyInfo {
+    // Stop describing the type once we're done (even if `info` panics):
+    struct Pop;
+    impl Drop for Pop {
+        fn drop(&mut self) {
+            DESCRIBING.with(|d| d.borrow_mut().pop());
+        }
+    }
+
+    // The type refers to itself, so we can't describe it inline again:
+    let is_recursive = DESCRIBING.with(|d| d.borrow().contains(&type_id));
+    if is_recursive {
+        return ref_to(name.to_owned())
+    }
+
+    DESCRIBING.with(|d| d.borrow_mut().push(type_id));
+    let _pop = Pop;
+    info()
+}
+
+fn ref_to(name: String) -> ApiBodyInfo {
+    ApiBodyInfo {
+        description: String::new(),
+        ty: ApiBodyType::Ref { name }
+    }
+}
@@ -0,0 +1,185 @@
+use std::collections::HashMap;
+use http::{ Response, StatusCode, Uri, header };
+use serde_json::{ Map, Value };
+use super::error::ApiError;
+use super::info::{ ApiBodyInfo, ApiBodyType };
+
+/// Implement this to decide how errors are handed back to API consumers. Hand this to
+/// [`crate::Api::render_errors()`] in order to have [`crate::Api::handle()`] convert errors
+/// into responses using it.
+///
+/// Some implementations are provided:
+///
+/// - [`JsonErrors`] hands back the JSON produced by [`ApiError::into_response()`].
+/// - [`ProblemDetails`] hands back [RFC 7807](https://www.rfc-editor.org/rfc/rfc7807)
+///   problem details (with the media type `application/problem+json`).
+///
+/// # Example
+///
+/// ```
+/// # use seamless::{ Api, ApiError, api::{ ApiBodyInfo, ErrorRenderer }, handler::{ request::Bytes, response::ToJson } };
+/// # use seamless::api::ApiBody;
+/// # use http::{ Response, Uri };
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// // Errors are handed back as `{ "error": "some message" }`:
+/// struct HouseErrors;
+///
+/// impl ErrorRenderer for HouseErrors {
+///     fn render(&self, error: ApiError, _uri: &Uri) -> Response<Vec<u8>> {
+///         let body = serde_json::json!({ "error": error.external_message });
+///         Response::builder()
+///             .status(error.code)
+///             .body(serde_json::to_vec(&body).unwrap())
+///             .unwrap()
+///     }
+///     fn response_body_info(&self) -> ApiBodyInfo {
+///         <std::collections::HashMap<String, String>>::api_body_info()
+///     }
+/// }
+///
+/// let mut api = Api::new();
+/// api.render_errors(HouseErrors);
+/// api.add("fails")
+///    .handler(|| Err::<ToJson<()>, _>(ApiError::path_not_found()));
+///
+/// let req = http::Request::get("/fails").body(Bytes::from_vec(vec![])).unwrap();
+/// let res = api.handle(req).await.unwrap();
+/// assert_eq!(res.status(), 404);
+/// assert_eq!(res.into_body(), br#"{"error":"Not found"}"#.to_vec());
+/// # })
+/// ```
+pub trait ErrorRenderer: Send + Sync + 'static {
+    /// Convert an error into the response to hand back. This is also given the URI of the
+    /// request that led to the error.
+    fn render(&self, error: ApiError, uri: &Uri) -> Response<Vec<u8>>;
+
+    /// A description of the body of the responses handed back from [`ErrorRenderer::render()`],
+    /// so that clients can know what shape errors will take.
+    fn response_body_info(&self) -> ApiBodyInfo;
+
+    /// The media type of the responses handed back from [`ErrorRenderer::render()`].
+    fn content_type(&self) -> &str {
+        "application/json"
+    }
+}
+
+/// An [`ErrorRenderer`] which hands back errors as JSON containing their `code`,
+/// `external_message` and `value`; see [`ApiError::into_response()`].
+#[derive(Debug,Clone,Copy,Default)]
+pub struct JsonErrors;
+
+impl ErrorRenderer for JsonErrors {
+    fn render(&self, error: ApiError, _uri: &Uri) -> Response<Vec<u8>> {
+        error.into_response()
+    }
+    fn response_body_info(&self) -> ApiBodyInfo {
+        ApiError::response_body_info()
+    }
+}
+
+/// An [`ErrorRenderer`] which hands back errors as [RFC 7807](https://www.rfc-editor.org/rfc/rfc7807)
+/// problem details, with the media type `application/problem+json`:
+///
+/// - `type` is the [`ApiError::problem_type`], or `about:blank` if there isn't one.
+/// - `title` is the reason phrase of the status code.
+/// - `status` is the status code.
+/// - `detail` is the [`ApiError::external_message`].
+/// - `instance` is the path of the request.
+///
+/// The [`ApiError::error_code`], if there is one, is added as the extension member `error_code`.
+/// If the [`ApiError::value`] is an object, its members are added alongside these as extension
+/// members. Any other value is added as the extension member `value`. Members of the value can't
+/// replace the standard members or `error_code`. Since the value differs from error to error, its
+/// members aren't described by [`ErrorRenderer::response_body_info()`].
+///
+/// # Example
+///
+/// ```
+/// # use seamless::{ Api, ApiError, api::ProblemDetails, handler::{ request::Bytes, response::ToJson } };
+/// # use serde_json::json;
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// #[derive(ApiError, Debug, thiserror::Error)]
+/// #[error("You do not have enough credit")]
+/// #[api_error(external, code = 403, type = "https://example.com/probs/out-of-credit")]
+/// struct OutOfCredit;
+///
+/// let mut api = Api::new();
+/// api.render_errors(ProblemDetails);
+/// api.add("buy")
+///    .handler(|| Err::<ToJson<()>, _>(OutOfCredit));
+///
+/// let req = http::Request::get("/buy").body(Bytes::from_vec(vec![])).unwrap();
+/// let res = api.handle(req).await.unwrap();
+/// assert_eq!(res.headers()["content-type"], "application/problem+json");
+///
+/// let body: serde_json::Value = serde_json::from_slice(res.body()).unwrap();
+/// assert_eq!(body, json!({
+///     "type": "https://example.com/probs/out-of-credit",
+///     "title": "Forbidden",
+///     "status": 403,
+///     "detail": "You do not have enough credit",
+///     "instance": "/buy",
+///     "error_code": "out_of_credit"
+/// }));
+/// # })
+/// ```
+#[derive(Debug,Clone,Copy,Default)]
+pub struct ProblemDetails;
+
+impl ProblemDetails {
+    const CONTENT_TYPE: &'static str = "application/problem+json";
+}
+
+impl ErrorRenderer for ProblemDetails {
+    fn render(&self, error: ApiError, uri: &Uri) -> Response<Vec<u8>> {
+        let status = StatusCode::from_u16(error.code).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+
+        // The error code takes precedence over members of the value with the same name:
+        let mut body = Map::new();
+        if let Some(error_code) = error.error_code {
+            body.insert("error_code".to_owned(), error_code.into());
+        }
+        let members = match error.value {
+            Some(Value::Object(members)) => members,
+            Some(Value::Null) | None => Map::new(),
+            Some(value) => std::iter::once(("value".to_owned(), value)).collect()
+        };
+        for (name, value) in members {
+            body.entry(name).or_insert(value);
+        }
+
+        // Extension members can't replace the standard members, so we add these last:
+        body.insert("type".to_owned(), error.problem_type.unwrap_or_else(|| "about:blank".to_owned()).into());
+        body.insert("title".to_owned(), status.canonical_reason().unwrap_or("Unknown Error").into());
+        body.insert("status".to_owned(), status.as_u16().into());
+        body.insert("detail".to_owned(), error.external_message.into());
+        body.insert("instance".to_owned(), uri.path().into());
+
+        Response::builder()
+            .status(status)
+            .header(header::CONTENT_TYPE, ProblemDetails::CONTENT_TYPE)
+            .body(serde_json::to_vec(&body).expect("problem details should serialize"))
+            .expect("valid response")
+    }
+    fn response_body_info(&self) -> ApiBodyInfo {
+        let key = |description: &str, ty: ApiBodyType| ApiBodyInfo { description: description.to_owned(), ty };
+        let keys: HashMap<String, ApiBodyInfo> = vec![
+            ("type", key("A URI identifying the type of problem", ApiBodyType::String)),
+            ("title", key("A short summary of the type of problem", ApiBodyType::String)),
+            ("status", key("The HTTP status code", ApiBodyType::Number)),
+            ("detail", key("A message describing this occurrence of the problem", ApiBodyType::String)),
+            ("instance", key("The path of the request which led to the problem", ApiBodyType::String)),
+            ("error_code", key("A code identifying the problem, if there is one", ApiBodyType::Optional {
+                value: Box::new(key("", ApiBodyType::String))
+            }))
+        ].into_iter().map(|(name, info)| (name.to_owned(), info)).collect();
+        ApiBodyInfo {
+            description: "An error returned from the API, as RFC 7807 problem details. Extension members \
+                taken from the value of the error aren't described here".to_owned(),
+            ty: ApiBodyType::Object { keys }
+        }
+    }
+    fn content_type(&self) -> &str {
+        ProblemDetails::CONTENT_TYPE
+    }
+}
@@ -0,0 +1,230 @@
+use std::collections::HashMap;
+use http::method::Method;
+use super::path::{ PathTemplate, Segment, percent_decode };
+
+/// Matches request paths against route paths. Routes are stored in a tree which branches
+/// on each segment of their path, so matching a request path only ever looks at the branches
+/// relevant to its segments; the cost of this doesn't grow with the number of routes.
+///
+/// When more than one route matches some path, static segments take priority over
+/// parameters, which take priority over catch-all parameters, working from left to right.
+pub (crate) struct Router<T> {
+    root: Node<T>
+}
+
+struct Node<T> {
+    // Children for each static segment:
+    statics: HashMap<String, Node<T>>,
+    // The child for any (non-empty) segment:
+    param: Option<Box<Node<T>>>,
+    // The routes which capture any (non-empty) remainder of the path:
+    catch_all: HashMap<Method, Route<T>>,
+    // The routes which end at this node:
+    routes: HashMap<Method, Route<T>>
+}
+
+struct Route<T> {
+    template: PathTemplate,
+    value: T
+}
+
+impl <T> Default for Node<T> {
+    fn default() -> Self {
+        Node {
+            statics: HashMap::new(),
+            param: None,
+            catch_all: HashMap::new(),
+            routes: HashMap::new()
+        }
+    }
+}
+
+impl <T> Router<T> {
+
+    pub fn new() -> Router<T> {
+        Router { root: Node::default() }
+    }
+
+    /// Add a route to the router. If a route with the same method and path exists already,
+    /// it is replaced and handed back. If a route exists with the same method and a path that
+    /// would match exactly the same requests (for instance, `users/{id}` and `users/{name}`),
+    /// the path of that route is handed back as an error.
+    pub fn insert(&mut self, method: Method, template: PathTemplate, value: T) -> Result<Option<T>, PathTemplate> {
+        let mut node = &mut self.root;
+        let mut routes = None;
+        for segment in template.segments() {
+            match segment {
+                Segment::Static(s) => {
+                    node = node.statics.entry(s.clone()).or_default();
+                },
+                Segment::Param(_) => {
+                    node = node.param.get_or_insert_with(Default::default);
+                },
+                Segment::CatchAll(_) => {
+                    routes = Some(&mut node.catch_all);
+                    break
+                }
+            }
+        }
+        let routes = match routes {
+            Some(routes) => routes,
+            None => &mut node.routes
+        };
+
+        if let Some(existing) = routes.get(&method) {
+            if existing.template != template {
+                return Err(existing.template.clone())
+            }
+        }
+        Ok(routes.insert(method, Route { template, value }).map(|r| r.value))
+    }
+
+    /// The route with the method provided whose path would match exactly the same requests
+    /// as the path provided, if there is one, along with its path.
+    pub fn existing(&self, method: &Method, template: &PathTemplate) -> Option<(&PathTemplate, &T)> {
+        let mut node = &self.root;
+        for segment in template.segments() {
+            match segment {
+                Segment::Static(s) => node = node.statics.get(s)?,
+                Segment::Param(_) => node = node.param.as_deref()?,
+                Segment::CatchAll(_) => {
+                    return node.catch_all.get(method).map(|r| (&r.template, &r.value))
+                }
+            }
+        }
+        node.routes.get(method).map(|r| (&r.template, &r.value))
+    }
+
+    /// The route with exactly the method and path provided, if there is one.
+    pub fn get_mut(&mut self, method: &Method, template: &PathTemplate) -> Option<&mut T> {
+        let mut node = &mut self.root;
+        let mut routes = None;
+        for segment in template.segments() {
+            match segment {
+                Segment::Static(s) => node = node.statics.get_mut(s)?,
+                Segment::Param(_) => node = node.param.as_deref_mut()?,
+                Segment::CatchAll(_) => {
+                    routes = Some(&mut node.catch_all);
+                    break
+                }
+            }
+        }
+        let route = routes.unwrap_or(&mut node.routes).get_mut(method)?;
+        if &route.template == template { Some(&mut route.value) } else { None }
+    }
+
+    /// Find the route matching the method and path provided, handing it back along
+    /// with the (percent decoded) values of any parameters in the path.
+    pub fn find(&self, method: &Method, path: &str) -> Option<(&T, Vec<(String,String)>)> {
+        let mut captures = vec![];
+        let route = self.root.find(method, Some(path), &mut captures)?;
+        let params = route.template.param_names()
+            .map(|name| name.to_owned())
+            .zip(captures)
+            .collect();
+        Some((&route.value, params))
+    }
+
+    /// The methods of every route whose path matches the path provided. This is
+    /// empty if no route matches the path.
+    pub fn allowed_methods(&self, path: &str) -> Vec<Method> {
+        let mut methods = vec![];
+        self.root.collect_methods(Some(path), &mut methods);
+        methods.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        methods.dedup();
+        methods
+    }
+
+    /// Every route in the router, alongside its method and path.
+    pub fn routes(&self) -> Vec<(&Method, &PathTemplate, &T)> {
+        let mut out = vec![];
+        self.root.collect_routes(&mut out);
+        out
+    }
+
+    /// Take every route out of the router, alongside its method and path.
+    pub fn into_routes(self) -> Vec<(Method, PathTemplate, T)> {
+        let mut out = vec![];
+        self.root.into_routes(&mut out);
+        out
+    }
+}
+
+impl <T> Node<T> {
+    // `path` is `None` once every segment has been consumed.
+    fn find(&self, method: &Method, path: Option<&str>, captures: &mut Vec<String>) -> Option<&Route<T>> {
+        let path = match path {
+            Some(path) => path,
+            None => return self.routes.get(method)
+        };
+        let (segment, rest) = match path.split_once('/') {
+            Some((segment, rest)) => (segment, Some(rest)),
+            None => (path, None)
+        };
+
+        if let Some(route) = self.statics.get(segment).and_then(|n| n.find(method, rest, captures)) {
+            return Some(route)
+        }
+
+        if segment.is_empty() {
+            return None
+        }
+
+        if let Some(node) = &self.param {
+            if let Some(value) = percent_decode(segment) {
+                captures.push(value);
+                if let Some(route) = node.find(method, rest, captures) {
+                    return Some(route)
+                }
+                captures.pop();
+            }
+        }
+
+        let route = self.catch_all.get(method)?;
+        captures.push(percent_decode(path)?);
+        Some(route)
+    }
+
+    // Unlike `find`, this follows every branch that matches the path.
+    fn collect_methods(&self, path: Option<&str>, methods: &mut Vec<Method>) {
+        let path = match path {
+            Some(path) => path,
+            None => return methods.extend(self.routes.keys().cloned())
+        };
+        let (segment, rest) = match path.split_once('/') {
+            Some((segment, rest)) => (segment, Some(rest)),
+            None => (path, None)
+        };
+
+        if let Some(node) = self.statics.get(segment) {
+            node.collect_methods(rest, methods);
+        }
+        if segment.is_empty() {
+            return
+        }
+        if let Some(node) = &self.param {
+            if percent_decode(segment).is_some() {
+                node.collect_methods(rest, methods);
+            }
+        }
+        if percent_decode(path).is_some() {
+            methods.extend(self.catch_all.keys().cloned());
+        }
+    }
+
+    fn into_routes(self, out: &mut Vec<(Method, PathTemplate, T)>) {
+        let routes = self.routes.into_iter().chain(self.catch_all);
+        out.extend(routes.map(|(method, route)| (method, route.template, route.value)));
+        for node in self.statics.into_values().chain(self.param.map(|n| *n)) {
+            node.into_routes(out);
+        }
+    }
+
+    fn collect_routes<'a>(&'a self, out: &mut Vec<(&'a Method, &'a PathTemplate, &'a T)>) {
+        let routes = self.routes.iter().chain(&self.catch_all);
+        out.extend(routes.map(|(method, route)| (method, &route.template, &route.value)));
+        for node in self.statics.values().chain(self.param.as_deref()) {
+            node.collect_routes(out);
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use http::{ HeaderMap, HeaderName, header };
+
+/// Implement this to decide which version of an [`crate::Api`] a request is for. Hand this
+/// to [`crate::Api::versioning()`] in order to route requests to the routes given a matching
+/// version using [`crate::api::RouteBuilder::version()`].
+///
+/// Some implementations are provided:
+///
+/// - [`PathVersion`] takes the version from the start of the request path (eg `/v2/users`).
+/// - [`HeaderVersion`] takes the version from a header (by default, `Accept-Version`).
+/// - [`MediaTypeVersion`] takes the version from a parameter of the media type given in the
+///   `Accept` header (eg `Accept: application/json; version=2`).
+pub trait VersionSelector: Send + Sync + 'static {
+    /// Given the headers and path of a request (relative to the base path of the API), hand back
+    /// the version being asked for (if any), along with the path that routes should be matched
+    /// against.
+    fn select<'a>(&self, headers: &HeaderMap, path: &'a str) -> (Option<String>, &'a str);
+
+    /// If requests ask for the version provided by prefixing their paths with something, hand
+    /// that prefix back. This is used to describe the path of each versioned route in
+    /// [`crate::Api::openapi()`]. By default, versions aren't part of the path.
+    fn path_prefix(&self, _version: &str) -> Option<String> {
+        None
+    }
+}
+
+/// A [`VersionSelector`] which takes the version from the first segment of the request
+/// path, if it's a `v` followed by a number (for instance `v1` or `v2.1`). The version is
+/// removed from the path before it's matched against routes, so a request to `/v2/users`
+/// is for the `users` route in version `v2`.
+#[derive(Debug,Clone,Copy,Default)]
+pub struct PathVersion;
+
+impl VersionSelector for PathVersion {
+    fn select<'a>(&self, _headers: &HeaderMap, path: &'a str) -> (Option<String>, &'a str) {
+        let (segment, rest) = path.split_once('/').unwrap_or((path, ""));
+        let is_version = segment.strip_prefix('v')
+            .map(|n| n.starts_with(|c: char| c.is_ascii_digit()) && n.chars().all(|c| c.is_ascii_digit() || c == '.'))
+            .unwrap_or(false);
+        if is_version {
+            (Some(segment.to_owned()), rest.trim_start_matches('/'))
+        } else {
+            (None, path)
+        }
+    }
+    fn path_prefix(&self, version: &str) -> Option<String> {
+        Some(version.to_owned())
+    }
+}
+
+/// A [`VersionSelector`] which takes the version from the value of some header. By
+/// default, this is the `Accept-Version` header.
+#[derive(Debug,Clone)]
+pub struct HeaderVersion {
+    name: HeaderName
+}
+
+impl HeaderVersion {
+    /// Take the version from the header with the name provided.
+    pub fn new(name: HeaderName) -> HeaderVersion {
+        HeaderVersion { name }
+    }
+}
+
+impl Default for HeaderVersion {
+    fn default() -> Self {
+        HeaderVersion::new(HeaderName::from_static("accept-version"))
+    }
+}
+
+impl VersionSelector for HeaderVersion {
+    fn select<'a>(&self, headers: &HeaderMap, path: &'a str) -> (Option<String>, &'a str) {
+        let version = headers.get(&self.name)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.trim().to_owned())
+            .filter(|v| !v.is_empty());
+        (version, path)
+    }
+}
+
+/// A [`VersionSelector`] which takes the version from a parameter of the media type
+/// given in the `Accept` header. By default this is the `version` parameter, so
+/// `Accept: application/json; version=2` asks for version `2`.
+#[derive(Debug,Clone)]
+pub struct MediaTypeVersion {
+    param: String
+}
+
+impl MediaTypeVersion {
+    /// Take the version from the media type parameter with the name provided.
+    pub fn new<S: Into<String>>(param: S) -> MediaTypeVersion {
+        MediaTypeVersion { param: param.into() }
+    }
+}
+
+impl Default for MediaTypeVersion {
+    fn default() -> Self {
+        MediaTypeVersion::new("version")
+    }
+}
+
+impl VersionSelector for MediaTypeVersion {
+    fn select<'a>(&self, headers: &HeaderMap, path: &'a str) -> (Option<String>, &'a str) {
+        let version = headers.get_all(header::ACCEPT)
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(|v| v.split(','))
+            .flat_map(|media_type| media_type.split(';').skip(1))
+            .filter_map(|param| param.split_once('='))
+            .find(|(name, _)| name.trim().eq_ignore_ascii_case(&self.param))
+            .map(|(_, value)| value.trim().trim_matches('"').to_owned());
+        (version, path)
+    }
+}
@@ -0,0 +1,37 @@
+//! Generate client code and other artifacts from the information handed back by
+//! [`crate::Api::info()`].
+
+pub mod typescript;
+pub mod openapi;
+
+// Split a route name like "maths/divide" or "maths.divide" into the words ["maths", "divide"].
+fn words(name: &str) -> Vec<&str> {
+    name.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect()
+}
+
+pub (crate) fn pascal_case(name: &str) -> String {
+    let mut out: String = words(name).into_iter().map(upper_first).collect();
+    if out.is_empty() || out.starts_with(|c: char| c.is_ascii_digit()) {
+        out.insert(0, '_');
+    }
+    out
+}
+
+pub (crate) fn camel_case(name: &str) -> String {
+    let mut out = pascal_case(name);
+    if let Some(c) = out.get(0..1) {
+        let lower = c.to_ascii_lowercase();
+        out.replace_range(0..1, &lower);
+    }
+    out
+}
+
+fn upper_first(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(c) => c.to_ascii_uppercase().to_string() + chars.as_str(),
+        None => String::new()
+    }
+}
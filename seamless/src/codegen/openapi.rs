@@ -0,0 +1,242 @@
+/*!
+Generate an [OpenAPI 3.1](https://spec.openapi.org/oas/v3.1.0) document describing the routes
+handed back by [`crate::Api::info()`]. [`crate::Api::openapi()`] is a convenient way to call this
+with the base path of some API. Each group that a route is in (see [`crate::Api::group()`]) is
+given to its operation as a tag, alongside any tags given to the route itself. Metadata attached to
+a route using [`crate::api::RouteBuilder::meta()`] is added to its operation as `x-` prefixed
+extensions. The fields expected in the query string (see [`crate::handler::param::Query`]) are
+each described as a query parameter, and the headers expected (see [`crate::handler::header`])
+as header parameters. Versioned routes are described as explained in [`crate::Api::openapi()`], and
+[`crate::Api::openapi_for_version()`] describes a single version of the API.
+
+The document is returned as a [`serde_json::Value`]. It contains some placeholder `info`, which
+you'll probably want to replace with something more meaningful before handing it out.
+
+# Example
+
+```rust
+use seamless::{
+    api::{ Api, ApiBody },
+    handler::{ body::FromJson, response::ToJson },
+};
+use serde_json::json;
+
+/// Two numbers to add together
+#[ApiBody]
+struct AddInput {
+    a: usize,
+    b: usize
+}
+
+let mut api = Api::new_with_base_path("api");
+api.add("maths/add")
+    .description("Add two numbers together")
+    .handler(|FromJson(body): FromJson<AddInput>| ToJson(body.a + body.b));
+
+let mut doc = api.openapi();
+doc["info"]["title"] = json!("Maths API");
+
+let op = &doc["paths"]["/api/maths/add"]["post"];
+assert_eq!(op["description"], json!("Add two numbers together"));
+assert_eq!(
+    op["requestBody"]["content"]["application/json"]["schema"],
+    json!({ "$ref": "#/components/schemas/AddInput" })
+);
+assert_eq!(
+    doc["components"]["schemas"]["AddInput"]["required"],
+    json!(["a", "b"])
+);
+```
+*/
+use std::collections::BTreeMap;
+use serde_json::{ json, Map, Value };
+use crate::api::{ ApiInfo, ApiBodyInfo, ApiBodyType };
+
+// Named types are placed in the components section of the document:
+static REF_PREFIX: &str = "#/components/schemas/";
+
+/// Generate an OpenAPI 3.1 document for the API provided, assuming that each
+/// route is available under the `base_path` given.
+pub fn generate(base_path: &str, info: &ApiInfo) -> Value {
+    let base_path = base_path.trim_matches('/');
+
+    let mut paths = Map::new();
+    let mut tags: Vec<Value> = vec![];
+    for route in &info.routes {
+        // OpenAPI has no notion of catch-all parameters, so `{*name}` becomes `{name}`:
+        let name = route.name.replace("{*", "{");
+        let path = if base_path.is_empty() {
+            format!("/{}", name)
+        } else {
+            format!("/{}/{}", base_path, name)
+        };
+
+        let mut operation = Map::new();
+        let mut op_tags: Vec<&str> = route.groups.iter().map(|g| g.name.as_str()).collect();
+        for tag in &route.tags {
+            if !op_tags.contains(&tag.as_str()) {
+                op_tags.push(tag);
+            }
+        }
+        if !op_tags.is_empty() {
+            operation.insert("tags".to_owned(), json!(op_tags));
+        }
+        operation.insert("operationId".to_owned(), json!(route.operation_id));
+        if !route.summary.is_empty() {
+            operation.insert("summary".to_owned(), json!(route.summary));
+        }
+        // OpenAPI can only say whether an operation is deprecated, so the message goes in the description:
+        let description = match &route.deprecated {
+            Some(message) if route.description.is_empty() => format!("Deprecated: {}", message),
+            Some(message) => format!("{}\n\nDeprecated: {}", route.description, message),
+            None => route.description.clone()
+        };
+        if !description.is_empty() {
+            operation.insert("description".to_owned(), json!(description));
+        }
+        if route.deprecated.is_some() {
+            operation.insert("deprecated".to_owned(), json!(true));
+        }
+        for (key, value) in &route.meta {
+            let key = if key.starts_with("x-") { key.clone() } else { format!("x-{}", key) };
+            operation.insert(key, value.clone());
+        }
+        for group in &route.groups {
+            if !tags.iter().any(|t| t["name"] == group.name.as_str()) {
+                let mut tag = json!({ "name": group.name });
+                if !group.description.is_empty() {
+                    tag["description"] = json!(group.description);
+                }
+                tags.push(tag);
+            }
+        }
+        let mut parameters: Vec<Value> = route.path_params.iter().map(|param| json!({
+            "name": param.name,
+            "in": "path",
+            "required": true,
+            "schema": param.ty.json_schema(REF_PREFIX)
+        })).collect();
+        if let Some(query) = &route.query_type {
+            parameters.extend(query_parameters(query, &info.definitions));
+        }
+        // OpenAPI ignores these headers if they're given as parameters, so we leave them out:
+        let headers = route.headers.iter().filter(|header| {
+            !matches!(header.name.as_str(), "accept" | "content-type" | "authorization")
+        });
+        parameters.extend(headers.map(|header| json!({
+            "name": header.name,
+            "in": "header",
+            "required": header.required,
+            "schema": header.ty.json_schema(REF_PREFIX)
+        })));
+        if !parameters.is_empty() {
+            operation.insert("parameters".to_owned(), json!(parameters));
+        }
+        // GET requests can't be given a body, so we don't describe one:
+        if route.method != "GET" {
+            operation.insert("requestBody".to_owned(), json!({
+                "required": true,
+                "content": content(&route.request_type)
+            }));
+        }
+        operation.insert("responses".to_owned(), json!({
+            "200": {
+                "description": non_empty_or(&route.response_type.description, "Success"),
+                "content": content(&route.response_type)
+            },
+            "default": {
+                "description": "Error",
+                "content": {
+                    info.error_response.content_type.clone(): {
+                        "schema": { "$ref": "#/components/schemas/ApiError" }
+                    }
+                }
+            }
+        }));
+
+        let path_item = paths.entry(path).or_insert_with(|| json!({}));
+        path_item[route.method.to_ascii_lowercase()] = Value::Object(operation);
+    }
+
+    let mut schemas = Map::new();
+    schemas.insert("ApiError".to_owned(), info.error_response.body.json_schema(REF_PREFIX));
+    for (name, definition) in &info.definitions {
+        schemas.insert(name.clone(), definition.json_schema(REF_PREFIX));
+    }
+
+    let mut doc = json!({
+        "openapi": "3.1.0",
+        "info": {
+            "title": "API",
+            "version": "0.0.0"
+        },
+        "paths": paths,
+        "components": {
+            "schemas": schemas
+        }
+    });
+    if !tags.is_empty() {
+        doc["tags"] = json!(tags);
+    }
+    doc
+}
+
+// Each field in the query string is described as a separate parameter. If we don't know
+// the names of the fields, a single parameter describes the whole query string instead.
+fn query_parameters(query: &ApiBodyInfo, definitions: &BTreeMap<String, ApiBodyInfo>) -> Vec<Value> {
+    let mut shape = query;
+    while let ApiBodyType::Ref { name } = &shape.ty {
+        match definitions.get(name) {
+            Some(definition) => shape = definition,
+            None => break
+        }
+    }
+
+    let keys = match &shape.ty {
+        ApiBodyType::Object { keys } => keys,
+        _ => return vec![json!({
+            "name": "query",
+            "in": "query",
+            "style": "form",
+            "explode": true,
+            "schema": query.json_schema(REF_PREFIX)
+        })]
+    };
+
+    // Sort the keys so that the output is deterministic:
+    let mut keys: Vec<_> = keys.iter().collect();
+    keys.sort_by(|a, b| a.0.cmp(b.0));
+    keys.into_iter().map(|(name, info)| {
+        let (required, value) = match &info.ty {
+            ApiBodyType::Optional { value } => (false, &**value),
+            _ => (true, info)
+        };
+        let mut param = json!({
+            "name": name,
+            "in": "query",
+            "required": required,
+            "schema": value.json_schema(REF_PREFIX)
+        });
+        let description = non_empty_or(&info.description, &value.description);
+        if !description.is_empty() {
+            param["description"] = json!(description);
+        }
+        param
+    }).collect()
+}
+
+// Binary data is sent as-is; everything else is expected to be JSON.
+fn content(info: &ApiBodyInfo) -> Value {
+    match info.ty {
+        ApiBodyType::Binary => json!({
+            "application/octet-stream": { "schema": info.describe_json_schema(json!({})) }
+        }),
+        _ => json!({
+            "application/json": { "schema": info.json_schema(REF_PREFIX) }
+        })
+    }
+}
+
+fn non_empty_or<'a>(s: &'a str, default: &'a str) -> &'a str {
+    if s.is_empty() { default } else { s }
+}
@@ -0,0 +1,529 @@
+/*!
+Generate a TypeScript API client from the information handed back by
+[`crate::Api::info_with_definitions()`].
+
+The output is a single self contained `.ts` file which contains a type declaration for
+every named type and every request and response shape (carrying over any doc comments),
+as well as a `createClient` function that hands back an object with a typed `fetch` based
+function for each route. Routes with parameters in their path (see [`crate::handler::param::Path`])
+take a `params` object which is used to fill them in, and routes which expect a query string (see
+[`crate::handler::param::Query`]) take a `query` object which is used to build it. Likewise, routes
+which expect headers (see [`crate::handler::header`]) take a `headers` object. The functions for routes added in a group
+(see [`crate::Api::group()`]) are nested in an object named after the group, so a route `users`
+in the group `admin` can be called using `client.admin.users()`. Functions are named after the
+operation ID of the route (see [`crate::api::RouteInfo::operation_id`]), minus the prefix of any
+group that they're in. Deprecated routes are marked as such, and log a warning when they are called.
+The errors that each route might hand back (see [`crate::api::RouteInfo::errors`]) are declared as a
+union of the JSON bodies produced by [`crate::api::ApiError::into_response()`], unless errors are
+rendered in some other way (see [`crate::Api::render_errors()`]). Client functions throw an
+`ApiRequestError` when a request fails, and document the union as the type of its `body`.
+
+To generate a client for a single version of an API (see [`crate::Api::versioning()`]), hand the
+output of [`crate::Api::info_with_definitions_for_version()`] to [`generate()`]. The client doesn't
+know how versions are selected, so configure it to ask for the right one (for instance using the
+`baseUrl` or `headers` options).
+
+# Example
+
+```rust
+use seamless::{
+    api::{ Api, ApiBody },
+    handler::{ body::FromJson, response::ToJson },
+    codegen::typescript,
+};
+
+/// Two numbers to add together
+#[ApiBody]
+struct AddInput {
+    a: usize,
+    b: usize
+}
+
+let mut api = Api::new();
+api.add("maths/add")
+    .description("Add two numbers together")
+    .handler(|FromJson(body): FromJson<AddInput>| ToJson(body.a + body.b));
+
+let ts = typescript::generate(&api.info_with_definitions());
+
+assert!(ts.contains("/** Two numbers to add together */\nexport interface AddInput {"));
+assert!(ts.contains("export type MathsAddRequest = AddInput;"));
+assert!(ts.contains("export type MathsAddResponse = number;"));
+assert!(ts.contains("mathsAdd: (body: MathsAddRequest): Promise<MathsAddResponse> =>"));
+```
+*/
+use std::fmt::Write;
+use crate::api::{ ApiInfo, RouteInfo, ApiBodyInfo, ApiBodyType, ApiError, ApiErrorInfo };
+use super::{ camel_case, pascal_case };
+
+static INDENT: &str = "    ";
+
+/// Generate the contents of a TypeScript file containing types and a client for the
+/// API provided.
+pub fn generate(info: &ApiInfo) -> String {
+    let routes = &info.routes;
+    let mut out = String::new();
+    out.push_str("// This file was generated by seamless. Do not edit it by hand.\n\n");
+
+    // Type declarations for each named type:
+    for (name, definition) in &info.definitions {
+        write_declaration(&mut out, name, definition);
+    }
+
+    // We only know what errors look like if they're handed back using `ApiError::into_response()`:
+    let describe_errors = info.error_response.body == ApiError::response_body_info();
+
+    // Type declarations for each set of path parameters, query parameters, headers, request, response and errors:
+    for route in routes {
+        let type_name = pascal_case(&route.operation_id);
+        if !route.path_params.is_empty() {
+            write_declaration(&mut out, &format!("{}Params", type_name), &path_params_type(route));
+        }
+        if let Some(query) = &route.query_type {
+            write_declaration(&mut out, &format!("{}Query", type_name), query);
+        }
+        if !route.headers.is_empty() {
+            write_declaration(&mut out, &format!("{}Headers", type_name), &headers_type(route));
+        }
+        if has_request_body(route) {
+            write_declaration(&mut out, &format!("{}Request", type_name), &route.request_type);
+        }
+        write_declaration(&mut out, &format!("{}Response", type_name), &route.response_type);
+        if describe_errors && !route.errors.is_empty() {
+            write_errors_declaration(&mut out, &format!("{}Error", type_name), &route.errors);
+        }
+    }
+
+    // The client, which has a function for each route:
+    let mut client = ClientGroup::default();
+    for route in routes {
+        client.insert(route);
+    }
+    client.dedupe_names();
+    out.push_str(CLIENT_PRELUDE);
+    client.write(&mut out, 2, describe_errors);
+    out.push_str(CLIENT_END);
+    out
+}
+
+// The functions and nested groups in some part of the client.
+#[derive(Default)]
+struct ClientGroup<'a> {
+    description: &'a str,
+    routes: Vec<(String, &'a RouteInfo)>,
+    groups: Vec<(String, ClientGroup<'a>)>
+}
+
+impl <'a> ClientGroup<'a> {
+    fn insert(&mut self, route: &'a RouteInfo) {
+        // Find the nested group that the route belongs in, and its path relative to that:
+        let mut group = self;
+        let mut fn_name = pascal_case(&route.operation_id);
+        for info in &route.groups {
+            // Operation IDs based on the route path begin with the group names, which we strip
+            // (so long as the prefix is a whole word, ie "Admin" in "AdminUsers" but not "AdministerUsers"):
+            let prefix = pascal_case(&info.name);
+            let is_word_prefix = fn_name.starts_with(&prefix) && fn_name[prefix.len()..]
+                .chars().next()
+                .map(|c| c.is_uppercase() || c.is_numeric())
+                .unwrap_or(false);
+            if is_word_prefix {
+                fn_name = fn_name[prefix.len()..].to_owned();
+            }
+            let name = camel_case(&info.name);
+            let idx = match group.groups.iter().position(|(n, _)| *n == name) {
+                Some(idx) => idx,
+                None => {
+                    group.groups.push((name, ClientGroup::default()));
+                    group.groups.len() - 1
+                }
+            };
+            group = &mut group.groups[idx].1;
+            if group.description.is_empty() {
+                group.description = &info.description;
+            }
+        }
+        group.routes.push((camel_case(&fn_name), route));
+    }
+
+    // Functions and groups are keys of the same object, so give any functions whose names clash
+    // with a group or an earlier function a numeric suffix (as we do for operation IDs):
+    fn dedupe_names(&mut self) {
+        let mut taken: Vec<String> = self.groups.iter().map(|(name, _)| name.clone()).collect();
+        for (fn_name, _) in &mut self.routes {
+            let mut name = fn_name.clone();
+            let mut n = 2;
+            while taken.contains(&name) {
+                name = format!("{}{}", fn_name, n);
+                n += 1;
+            }
+            *fn_name = name.clone();
+            taken.push(name);
+        }
+        for (_, group) in &mut self.groups {
+            group.dedupe_names();
+        }
+    }
+
+    fn write(&self, out: &mut String, indent: usize, describe_errors: bool) {
+        for (fn_name, route) in &self.routes {
+            write_client_fn(out, fn_name, route, indent, describe_errors);
+        }
+        for (name, group) in &self.groups {
+            write_doc(out, group.description, indent);
+            writeln!(out, "{}{}: {{", INDENT.repeat(indent), name).unwrap();
+            group.write(out, indent + 1, describe_errors);
+            writeln!(out, "{}}},", INDENT.repeat(indent)).unwrap();
+        }
+    }
+}
+
+static CLIENT_PRELUDE: &str = r#"/**
+ * The error thrown when an API route responds with an unsuccessful status code. Client functions
+ * document the type of `body` for the errors that their route is known to respond with.
+ */
+export class ApiRequestError<Body = unknown> extends Error {
+    constructor(public readonly status: number, public readonly body: Body) {
+        super(`Request failed with status ${status}`);
+    }
+}
+
+/** Options to configure the API client with. */
+export interface ClientOptions {
+    /** The URL that route paths are relative to, eg "https://example.com/api". */
+    baseUrl?: string;
+    /** Headers to send along with every request. */
+    headers?: Record<string, string>;
+    /** The `fetch` implementation to use. Defaults to the global `fetch`. */
+    fetch?: typeof fetch;
+}
+
+type RequestKind = "none" | "json" | "binary";
+type ResponseKind = "json" | "binary";
+
+/** Create a client which can make requests to each of the API routes. */
+export function createClient(options: ClientOptions = {}) {
+    const baseUrl = (options.baseUrl ?? "").replace(/\/+$/, "");
+    const doFetch = options.fetch ?? fetch;
+
+    function withQuery(path: string, query: object): string {
+        const params = new URLSearchParams();
+        for (const [key, value] of Object.entries(query)) {
+            for (const v of Array.isArray(value) ? value : [value]) {
+                if (v !== undefined && v !== null) params.append(key, String(v));
+            }
+        }
+        const queryString = params.toString();
+        return queryString ? `${path}?${queryString}` : path;
+    }
+
+    async function call(method: string, path: string, requestKind: RequestKind, responseKind: ResponseKind, body?: unknown, routeHeaders: object = {}): Promise<any> {
+        const headers: Record<string, string> = { ...options.headers };
+        for (const [key, value] of Object.entries(routeHeaders)) {
+            if (value !== undefined && value !== null) headers[key] = String(value);
+        }
+        let requestBody: BodyInit | undefined = undefined;
+        if (requestKind === "json") {
+            headers["content-type"] = "application/json";
+            requestBody = JSON.stringify(body);
+        } else if (requestKind === "binary") {
+            headers["content-type"] = "application/octet-stream";
+            requestBody = body as Blob;
+        }
+
+        const res = await doFetch(`${baseUrl}/${path}`, { method, headers, body: requestBody });
+        if (!res.ok) {
+            const text = await res.text();
+            let errorBody: unknown = text;
+            try { errorBody = JSON.parse(text); } catch {}
+            throw new ApiRequestError(res.status, errorBody);
+        }
+        return responseKind === "binary" ? res.blob() : res.json();
+    }
+
+    return {
+"#;
+
+static CLIENT_END: &str = "    };
+}
+";
+
+// GET requests can't be given a body, so we don't ask for one.
+fn has_request_body(route: &RouteInfo) -> bool {
+    route.method != "GET"
+}
+
+fn write_declaration(out: &mut String, name: &str, info: &ApiBodyInfo) {
+    write_doc(out, &info.description, 0);
+    match &info.ty {
+        ApiBodyType::Object { keys } => {
+            writeln!(out, "export interface {} {}", name, ts_object(keys, 0)).unwrap();
+        },
+        _ => {
+            writeln!(out, "export type {} = {};", name, ts_type(info, 0)).unwrap();
+        }
+    }
+    out.push('\n');
+}
+
+// The errors that a route might hand back are declared as a union, which can be
+// narrowed down using the `code` or `error_code` of each error.
+fn write_errors_declaration(out: &mut String, name: &str, errors: &[ApiErrorInfo]) {
+    write_doc(out, "The errors that the route might respond with.", 0);
+    writeln!(out, "export type {} =", name).unwrap();
+    for (idx, error) in errors.iter().enumerate() {
+        let message = match &error.external_message {
+            Some(message) => serde_json::to_string(message).unwrap(),
+            None => "string".to_owned()
+        };
+        let error_code = match &error.error_code {
+            Some(error_code) => serde_json::to_string(error_code).unwrap(),
+            None => "null".to_owned()
+        };
+        let value = match &error.value {
+            Some(value) => ts_type(value, 1),
+            None => "null".to_owned()
+        };
+        let end = if idx + 1 == errors.len() { ";" } else { "" };
+        writeln!(out, "{}| {{ code: {}; error_code: {}; external_message: {}; value: {} }}{}", INDENT, error.code, error_code, message, value, end).unwrap();
+    }
+    out.push('\n');
+}
+
+// Path parameters are handed to client functions as an object:
+fn path_params_type(route: &RouteInfo) -> ApiBodyInfo {
+    ApiBodyInfo {
+        description: String::new(),
+        ty: ApiBodyType::Object {
+            keys: route.path_params.iter().map(|p| (p.name.clone(), p.ty.clone())).collect()
+        }
+    }
+}
+
+// Headers are handed to client functions as an object, too:
+fn headers_type(route: &RouteInfo) -> ApiBodyInfo {
+    let keys = route.headers.iter().map(|header| {
+        let ty = if header.required {
+            header.ty.clone()
+        } else {
+            ApiBodyInfo { description: String::new(), ty: ApiBodyType::Optional { value: Box::new(header.ty.clone()) } }
+        };
+        (header.name.clone(), ty)
+    });
+    ApiBodyInfo {
+        description: String::new(),
+        ty: ApiBodyType::Object { keys: keys.collect() }
+    }
+}
+
+fn write_client_fn(out: &mut String, fn_name: &str, route: &RouteInfo, indent_level: usize, describe_errors: bool) {
+    let indent = INDENT.repeat(indent_level);
+    let type_name = pascal_case(&route.operation_id);
+    let path = match &route.query_type {
+        Some(_) => format!("withQuery({}, query)", ts_path(route)),
+        None => ts_path(route)
+    };
+    let method = serde_json::to_string(&route.method).unwrap();
+    let response_kind = if is_binary(&route.response_type) { "\"binary\"" } else { "\"json\"" };
+
+    let mut args = vec![];
+    if !route.path_params.is_empty() {
+        args.push(format!("params: {}Params", type_name));
+    }
+    if route.query_type.is_some() {
+        args.push(format!("query: {}Query", type_name));
+    }
+    if !route.headers.is_empty() {
+        args.push(format!("headers: {}Headers", type_name));
+    }
+    if has_request_body(route) {
+        args.push(format!("body: {}Request", type_name));
+    }
+
+    write_doc(out, &route_doc(route, describe_errors), indent_level);
+    write!(out, "{}{}: ({}): Promise<{}Response> =>", indent, fn_name, args.join(", "), type_name).unwrap();
+    let headers = if route.headers.is_empty() { "" } else { ", headers" };
+    let call = if has_request_body(route) {
+        let request_kind = if is_binary(&route.request_type) { "\"binary\"" } else { "\"json\"" };
+        format!("call({}, {}, {}, {}, body{})", method, path, request_kind, response_kind, headers)
+    } else if route.headers.is_empty() {
+        format!("call({}, {}, \"none\", {})", method, path, response_kind)
+    } else {
+        format!("call({}, {}, \"none\", {}, undefined, headers)", method, path, response_kind)
+    };
+    match &route.deprecated {
+        Some(message) => {
+            let warning = format!("The route {} {} is deprecated: {}", route.method, route.name, message);
+            writeln!(out, " {{").unwrap();
+            writeln!(out, "{}{}console.warn({});", indent, INDENT, serde_json::to_string(&warning).unwrap()).unwrap();
+            writeln!(out, "{}{}return {};", indent, INDENT, call).unwrap();
+            writeln!(out, "{}}},", indent).unwrap();
+        },
+        None => {
+            writeln!(out).unwrap();
+            writeln!(out, "{}{}{},", indent, INDENT, call).unwrap();
+        }
+    }
+}
+
+// The doc comment for a route's client function. If we've declared the errors that
+// the route might respond with, the error thrown is documented in terms of them.
+fn route_doc(route: &RouteInfo, describe_errors: bool) -> String {
+    let mut parts = vec![];
+    for part in [&route.summary, &route.description] {
+        if !part.trim().is_empty() {
+            parts.push(part.trim().to_owned());
+        }
+    }
+    if let Some(message) = &route.deprecated {
+        parts.push(format!("@deprecated {}", message));
+    }
+    if describe_errors && !route.errors.is_empty() {
+        parts.push(format!("@throws {{ApiRequestError<{}Error>}}", pascal_case(&route.operation_id)));
+    }
+    parts.join("\n\n")
+}
+
+// The route path as a TypeScript expression. Path parameters are substituted
+// in (URI encoded) from the `params` argument.
+fn ts_path(route: &RouteInfo) -> String {
+    if route.path_params.is_empty() {
+        return serde_json::to_string(&route.name).unwrap()
+    }
+    let segments: Vec<String> = route.name.split('/').map(|segment| {
+        match segment.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+            // Catch-all parameters can contain slashes, so we leave those alone:
+            Some(name) if name.starts_with('*') => {
+                format!("${{String(params{}).split(\"/\").map(encodeURIComponent).join(\"/\")}}", ts_property(&name[1..]))
+            },
+            Some(name) => format!("${{encodeURIComponent(String(params{}))}}", ts_property(name)),
+            None => segment.replace('\\', "\\\\").replace('`', "\\`").replace('$', "\\$")
+        }
+    }).collect();
+    format!("`{}`", segments.join("/"))
+}
+
+fn is_binary(info: &ApiBodyInfo) -> bool {
+    matches!(info.ty, ApiBodyType::Binary)
+}
+
+/// Render the TypeScript type corresponding to some [`ApiBodyInfo`]. Multi-line types
+/// (ie objects) are indented assuming that they start at the indent level provided.
+fn ts_type(info: &ApiBodyInfo, indent: usize) -> String {
+    match &info.ty {
+        ApiBodyType::String => "string".to_owned(),
+        ApiBodyType::Number => "number".to_owned(),
+        ApiBodyType::Boolean => "boolean".to_owned(),
+        ApiBodyType::Null => "null".to_owned(),
+        ApiBodyType::Any => "any".to_owned(),
+        ApiBodyType::Unknown => "unknown".to_owned(),
+        ApiBodyType::Binary => "Blob".to_owned(),
+        ApiBodyType::ArrayOf { value } => {
+            let inner = ts_type(value, indent);
+            if is_union(value) {
+                format!("({})[]", inner)
+            } else {
+                format!("{}[]", inner)
+            }
+        },
+        ApiBodyType::TupleOf { values } => {
+            let values: Vec<_> = values.iter().map(|v| ts_type(v, indent)).collect();
+            format!("[{}]", values.join(", "))
+        },
+        ApiBodyType::ObjectOf { value } => {
+            format!("{{ [key: string]: {} }}", ts_type(value, indent))
+        },
+        ApiBodyType::Object { keys } => {
+            ts_object(keys, indent)
+        },
+        ApiBodyType::OneOf { values } => {
+            if values.is_empty() {
+                return "never".to_owned()
+            }
+            let values: Vec<_> = values.iter().map(|v| ts_type(v, indent)).collect();
+            values.join(" | ")
+        },
+        ApiBodyType::StringLiteral { literal } => {
+            serde_json::to_string(literal).unwrap()
+        },
+        ApiBodyType::Optional { value } => {
+            format!("{} | undefined", ts_type(value, indent))
+        },
+        ApiBodyType::Ref { name } => {
+            name.clone()
+        }
+    }
+}
+
+fn ts_object(keys: &std::collections::HashMap<String, ApiBodyInfo>, indent: usize) -> String {
+    if keys.is_empty() {
+        return "{}".to_owned()
+    }
+
+    // Sort the keys so that the output is deterministic:
+    let mut keys: Vec<_> = keys.iter().collect();
+    keys.sort_by(|a, b| a.0.cmp(b.0));
+
+    let mut out = String::from("{\n");
+    for (key, info) in keys {
+        // Optional values are expressed via `key?: value` in objects:
+        let (optional, value) = match &info.ty {
+            ApiBodyType::Optional { value } => ("?", &**value),
+            _ => ("", info)
+        };
+        let description = if info.description.is_empty() { &value.description } else { &info.description };
+        write_doc(&mut out, description, indent + 1);
+        writeln!(out, "{}{}{}: {};", INDENT.repeat(indent + 1), ts_key(key), optional, ts_type(value, indent + 1)).unwrap();
+    }
+    out.push_str(&INDENT.repeat(indent));
+    out.push('}');
+    out
+}
+
+fn is_union(info: &ApiBodyInfo) -> bool {
+    match &info.ty {
+        ApiBodyType::OneOf { values } => values.len() > 1,
+        ApiBodyType::Optional { .. } => true,
+        _ => false
+    }
+}
+
+fn ts_key(key: &str) -> String {
+    let mut chars = key.chars();
+    let is_ident = chars.next().map(|c| c.is_ascii_alphabetic() || c == '_' || c == '$').unwrap_or(false)
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+    if is_ident {
+        key.to_owned()
+    } else {
+        serde_json::to_string(key).unwrap()
+    }
+}
+
+// Access some property, falling back to `["key"]` if it's not a valid identifier.
+fn ts_property(key: &str) -> String {
+    let key_str = ts_key(key);
+    if key_str == key {
+        format!(".{}", key)
+    } else {
+        format!("[{}]", key_str)
+    }
+}
+
+fn write_doc(out: &mut String, description: &str, indent: usize) {
+    let description = description.trim();
+    if description.is_empty() {
+        return
+    }
+    let indent = INDENT.repeat(indent);
+    let description = description.replace("*/", "*\\/");
+    let lines: Vec<_> = description.lines().collect();
+    if lines.len() == 1 {
+        writeln!(out, "{}/** {} */", indent, lines[0]).unwrap();
+    } else {
+        writeln!(out, "{}/**", indent).unwrap();
+        for line in lines {
+            writeln!(out, "{} *{}{}", indent, if line.is_empty() { "" } else { " " }, line).unwrap();
+        }
+        writeln!(out, "{} */", indent).unwrap();
+    }
+}
@@ -64,7 +64,9 @@ impl <T: DeserializeOwned + ApiBody> HandlerBody for FromJson<T> {
                 code: 400,
                 internal_message: e.to_string(),
                 external_message: e.to_string(),
-                value: None
+                value: None,
+                problem_type: None,
+                error_code: None
             })?;
 
         // Assume JSON and parse:
@@ -73,7 +75,9 @@ impl <T: DeserializeOwned + ApiBody> HandlerBody for FromJson<T> {
                 code: 400,
                 internal_message: e.to_string(),
                 external_message: e.to_string(),
-                value: None
+                value: None,
+                problem_type: None,
+                error_code: None
             })?;
         Ok(FromJson(json))
     }
@@ -110,7 +114,9 @@ fn content_type_not_json_err() -> ApiError {
         code: 415,
         internal_message: "Content-Type must be application/json".to_string(),
         external_message: "Content-Type must be application/json".to_string(),
-        value: None
+        value: None,
+        problem_type: None,
+        error_code: None
     }
 }
 
@@ -128,7 +134,9 @@ impl HandlerBody for FromBinary {
                 code: 400,
                 internal_message: e.to_string(),
                 external_message: e.to_string(),
-                value: None
+                value: None,
+                problem_type: None,
+                error_code: None
             })?;
         Ok(FromBinary(body))
     }
@@ -0,0 +1,387 @@
+use std::fmt;
+use std::sync::{ Arc, Mutex };
+use std::time::Duration;
+use http::{ HeaderMap, HeaderValue, Request, header };
+use async_trait::async_trait;
+use crate::api::{ percent_decode };
+use super::param::HandlerParam;
+
+/// Ask for this in a handler in order to read the cookies sent with the request, and to set
+/// cookies on the response. Cookies that are added or removed are sent back in `Set-Cookie`
+/// headers if the handler succeeds (and are discarded if it returns an error).
+///
+/// With the `secure-cookies` feature enabled, cookies can also be signed (so that they can be
+/// read but not tampered with) or made private (so that they can be neither read nor tampered
+/// with) using the key given to [`crate::Api::cookie_key()`].
+///
+/// # Example
+///
+/// ```
+/// # use seamless::{ Api, handler::{ cookie::{ Cookie, Cookies }, response::ToJson } };
+/// # let mut api = Api::new();
+/// api.add("visits")
+///    .handler(|cookies: Cookies| {
+///        let visits: usize = cookies.get("visits").and_then(|v| v.parse().ok()).unwrap_or(0);
+///        cookies.add(Cookie::new("visits", (visits + 1).to_string()).http_only(true));
+///        ToJson(visits)
+///    });
+/// ```
+#[derive(Debug,Clone)]
+pub struct Cookies {
+    cookies: Vec<(String, String)>,
+    jar: CookieJar
+}
+
+impl Cookies {
+    /// The value of the cookie with the name provided, if the request has one.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.cookies.iter().find(|(n, _)| n == name).map(|(_, value)| value.as_str())
+    }
+
+    /// Iterate over the names and values of the cookies sent with the request.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.cookies.iter().map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+
+    /// Set a cookie on the response.
+    pub fn add(&self, cookie: Cookie) {
+        self.jar.0.lock().unwrap().changes.push(cookie);
+    }
+
+    /// Tell the client to remove a cookie. The path and domain of the cookie provided
+    /// should match those that it was set with; its value and other attributes are ignored.
+    pub fn remove(&self, cookie: Cookie) {
+        let mut cookie = cookie.max_age(Duration::ZERO);
+        cookie.value = String::new();
+        self.add(cookie);
+    }
+}
+
+#[async_trait]
+impl HandlerParam for Cookies {
+    type Error = std::convert::Infallible;
+    async fn handler_param(req: &Request<()>) -> Result<Self,Self::Error> {
+        Ok(Cookies {
+            cookies: parse_cookies(req.headers()),
+            // The jar is placed into the request by the API router, but we make do without
+            // if not (for instance if the handler is called some other way):
+            jar: req.extensions().get::<CookieJar>().cloned().unwrap_or_default()
+        })
+    }
+}
+
+// Parse the cookies from any `Cookie` headers. Values are percent decoded if possible.
+fn parse_cookies(headers: &HeaderMap) -> Vec<(String, String)> {
+    headers.get_all(header::COOKIE)
+        .iter()
+        .filter_map(|h| h.to_str().ok())
+        .flat_map(|h| h.split(';'))
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(name, value)| {
+            let value = value.trim().trim_matches('"');
+            (name.trim().to_owned(), percent_decode(value).unwrap_or_else(|| value.to_owned()))
+        })
+        .filter(|(name, _)| !name.is_empty())
+        .collect()
+}
+
+// The API router places this into requests so that [`Cookies`] can record the cookies to set,
+// and the router can then add them to the response.
+#[derive(Debug,Clone,Default)]
+pub (crate) struct CookieJar(Arc<Mutex<CookieJarInner>>);
+
+#[derive(Debug,Default)]
+struct CookieJarInner {
+    changes: Vec<Cookie>,
+    #[cfg(feature = "secure-cookies")]
+    key: Option<Arc<CookieKey>>
+}
+
+impl CookieJar {
+    /// A jar which can sign and encrypt cookies using the key provided.
+    #[cfg(feature = "secure-cookies")]
+    pub (crate) fn with_key(key: Option<Arc<CookieKey>>) -> CookieJar {
+        CookieJar(Arc::new(Mutex::new(CookieJarInner { changes: vec![], key })))
+    }
+
+    /// Add a `Set-Cookie` header to the response for each of the cookies that have been set.
+    pub (crate) fn add_headers(&self, headers: &mut HeaderMap) {
+        for cookie in self.0.lock().unwrap().changes.drain(..) {
+            if let Ok(value) = HeaderValue::from_str(&cookie.to_string()) {
+                headers.append(header::SET_COOKIE, value);
+            }
+        }
+    }
+}
+
+/// A cookie to set on the response; see [`Cookies::add()`].
+#[derive(Debug,Clone,PartialEq)]
+pub struct Cookie {
+    name: String,
+    value: String,
+    path: Option<String>,
+    domain: Option<String>,
+    max_age: Option<Duration>,
+    secure: bool,
+    http_only: bool,
+    same_site: Option<SameSite>
+}
+
+/// The value of the `SameSite` attribute of a [`Cookie`].
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub enum SameSite {
+    /// Only send the cookie with requests from the same site.
+    Strict,
+    /// Also send the cookie when navigating to the site from elsewhere.
+    Lax,
+    /// Send the cookie with every request (this requires the cookie to be secure).
+    None
+}
+
+impl Cookie {
+    /// Create a new cookie with the name and value provided. Characters in the value
+    /// which aren't allowed in cookies are percent encoded (and [`Cookies`] decodes them).
+    pub fn new<N: Into<String>, V: Into<String>>(name: N, value: V) -> Cookie {
+        Cookie {
+            name: name.into(),
+            value: value.into(),
+            path: None,
+            domain: None,
+            max_age: None,
+            secure: false,
+            http_only: false,
+            same_site: None
+        }
+    }
+
+    /// The name of the cookie.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The value of the cookie.
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// Only send the cookie with requests to the path provided (and paths below it).
+    pub fn path<S: Into<String>>(mut self, path: S) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    /// Send the cookie with requests to the domain provided (and its subdomains).
+    pub fn domain<S: Into<String>>(mut self, domain: S) -> Self {
+        self.domain = Some(domain.into());
+        self
+    }
+
+    /// How long the cookie should be kept for. By default, it's removed when the browser is closed.
+    pub fn max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// Only send the cookie over HTTPS.
+    pub fn secure(mut self, secure: bool) -> Self {
+        self.secure = secure;
+        self
+    }
+
+    /// Prevent the cookie from being read by scripts in the browser.
+    pub fn http_only(mut self, http_only: bool) -> Self {
+        self.http_only = http_only;
+        self
+    }
+
+    /// Set the `SameSite` attribute of the cookie.
+    pub fn same_site(mut self, same_site: SameSite) -> Self {
+        self.same_site = Some(same_site);
+        self
+    }
+}
+
+/// Format the cookie as the value of a `Set-Cookie` header.
+impl fmt::Display for Cookie {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}={}", self.name, encode_value(&self.value))?;
+        if let Some(path) = &self.path {
+            write!(f, "; Path={}", path)?;
+        }
+        if let Some(domain) = &self.domain {
+            write!(f, "; Domain={}", domain)?;
+        }
+        if let Some(max_age) = self.max_age {
+            write!(f, "; Max-Age={}", max_age.as_secs())?;
+        }
+        if self.secure {
+            write!(f, "; Secure")?;
+        }
+        if self.http_only {
+            write!(f, "; HttpOnly")?;
+        }
+        match self.same_site {
+            Some(SameSite::Strict) => write!(f, "; SameSite=Strict")?,
+            Some(SameSite::Lax) => write!(f, "; SameSite=Lax")?,
+            Some(SameSite::None) => write!(f, "; SameSite=None")?,
+            None => {}
+        }
+        Ok(())
+    }
+}
+
+// Percent encode anything which isn't allowed in a cookie value (and '%' itself).
+fn encode_value(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for b in value.bytes() {
+        let allowed = b.is_ascii_graphic() && !matches!(b, b'"' | b',' | b';' | b'\\' | b'%');
+        if allowed {
+            out.push(b as char);
+        } else {
+            out.push_str(&format!("%{:02X}", b));
+        }
+    }
+    out
+}
+
+#[cfg(feature = "secure-cookies")]
+pub use secure::CookieKey;
+
+#[cfg(feature = "secure-cookies")]
+mod secure {
+    use std::sync::Arc;
+    use aes_gcm::{ Aes256Gcm, KeyInit, aead::{ Aead, Payload } };
+    use base64::{ Engine, engine::general_purpose::STANDARD as BASE64 };
+    use hmac::{ Hmac, Mac };
+    use sha2::Sha256;
+    use crate::api::ApiError;
+    use super::{ Cookie, Cookies };
+
+    const NONCE_LEN: usize = 12;
+
+    /// The key used to sign and encrypt cookies; hand this to [`crate::Api::cookie_key()`].
+    /// The same key must be used to read cookies as was used to set them, so it should be
+    /// kept somewhere safe and shared between every instance of the API.
+    #[derive(Clone)]
+    pub struct CookieKey {
+        signing: [u8; 32],
+        encryption: [u8; 32]
+    }
+
+    impl CookieKey {
+        /// Create a key from at least 64 bytes of random data, handing back `None` if fewer
+        /// bytes are provided. Half of the key is used for signing, and half for encryption.
+        pub fn from_bytes(bytes: &[u8]) -> Option<CookieKey> {
+            if bytes.len() < 64 {
+                return None
+            }
+            let mut signing = [0; 32];
+            let mut encryption = [0; 32];
+            signing.copy_from_slice(&bytes[..32]);
+            encryption.copy_from_slice(&bytes[32..64]);
+            Some(CookieKey { signing, encryption })
+        }
+
+        /// Generate a new random key. Cookies set using this key can't be read using
+        /// any other key, so this is mainly useful for testing.
+        pub fn generate() -> CookieKey {
+            let mut bytes = [0; 64];
+            getrandom::getrandom(&mut bytes).expect("failed to generate a random cookie key");
+            CookieKey::from_bytes(&bytes).unwrap()
+        }
+
+        // The signature covers the name too, so that values can't be moved between cookies. The
+        // name is prefixed with its length, so that "ab" = "c" and "a" = "bc" are signed differently.
+        fn signature(&self, name: &str, value: &str) -> Hmac<Sha256> {
+            let mut mac = <Hmac::<Sha256> as Mac>::new_from_slice(&self.signing).expect("any key length is valid");
+            mac.update(&(name.len() as u64).to_be_bytes());
+            mac.update(name.as_bytes());
+            mac.update(value.as_bytes());
+            mac
+        }
+
+        fn sign(&self, name: &str, value: &str) -> String {
+            let signature = self.signature(name, value).finalize().into_bytes();
+            format!("{}{}", BASE64.encode(signature), value)
+        }
+
+        fn verify(&self, name: &str, signed: &str) -> Option<String> {
+            // Signatures are 32 bytes, which is always 44 characters of base64:
+            let (signature, value) = (signed.get(..44)?, signed.get(44..)?);
+            let signature = BASE64.decode(signature).ok()?;
+            self.signature(name, value).verify_slice(&signature).ok()?;
+            Some(value.to_owned())
+        }
+
+        fn encrypt(&self, name: &str, value: &str) -> String {
+            let mut nonce = [0; NONCE_LEN];
+            getrandom::getrandom(&mut nonce).expect("failed to generate a random nonce");
+            let cipher = Aes256Gcm::new(&self.encryption.into());
+            let encrypted = cipher.encrypt(&nonce.into(), Payload { msg: value.as_bytes(), aad: name.as_bytes() })
+                .expect("encrypting a cookie should not fail");
+            let mut data = nonce.to_vec();
+            data.extend(encrypted);
+            BASE64.encode(data)
+        }
+
+        fn decrypt(&self, name: &str, encrypted: &str) -> Option<String> {
+            let data = BASE64.decode(encrypted).ok()?;
+            if data.len() < NONCE_LEN {
+                return None
+            }
+            let (nonce, encrypted) = data.split_at(NONCE_LEN);
+            let cipher = Aes256Gcm::new(&self.encryption.into());
+            let value = cipher.decrypt(nonce.into(), Payload { msg: encrypted, aad: name.as_bytes() }).ok()?;
+            String::from_utf8(value).ok()
+        }
+    }
+
+    impl std::fmt::Debug for CookieKey {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            // Don't leak the key into any logs:
+            f.write_str("CookieKey(..)")
+        }
+    }
+
+    impl Cookies {
+        /// The value of the signed cookie with the name provided, if the request has one and
+        /// its signature is valid. Signed cookies are set using [`Cookies::add_signed()`].
+        pub fn get_signed(&self, name: &str) -> Option<String> {
+            let value = self.get(name)?;
+            self.key()?.verify(name, value)
+        }
+
+        /// Set a cookie on the response which is signed, so that its value can be read by
+        /// the client but any changes to it will be detected. This fails if no key has been
+        /// given to [`crate::Api::cookie_key()`].
+        pub fn add_signed(&self, mut cookie: Cookie) -> Result<(), ApiError> {
+            cookie.value = self.require_key()?.sign(&cookie.name, &cookie.value);
+            self.add(cookie);
+            Ok(())
+        }
+
+        /// The value of the private cookie with the name provided, if the request has one and
+        /// it can be decrypted. Private cookies are set using [`Cookies::add_private()`].
+        pub fn get_private(&self, name: &str) -> Option<String> {
+            let value = self.get(name)?;
+            self.key()?.decrypt(name, value)
+        }
+
+        /// Set a cookie on the response which is encrypted, so that its value can be neither
+        /// read nor changed by the client. This fails if no key has been given to
+        /// [`crate::Api::cookie_key()`].
+        pub fn add_private(&self, mut cookie: Cookie) -> Result<(), ApiError> {
+            cookie.value = self.require_key()?.encrypt(&cookie.name, &cookie.value);
+            self.add(cookie);
+            Ok(())
+        }
+
+        fn key(&self) -> Option<Arc<CookieKey>> {
+            self.jar.0.lock().unwrap().key.clone()
+        }
+
+        fn require_key(&self) -> Result<Arc<CookieKey>, ApiError> {
+            self.key().ok_or_else(|| ApiError::server_error("No cookie key has been configured; see Api::cookie_key()"))
+        }
+    }
+}
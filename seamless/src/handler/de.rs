@@ -0,0 +1,322 @@
+//! Serde deserializers for turning strings from the request (eg path segments)
+//! into arbitrary types.
+use serde::de::{ self, Deserializer, IntoDeserializer, Visitor, MapAccess, SeqAccess, value::Error };
+
+/// Deserialize some type from a list of named string values. Structs and maps are deserialized
+/// by name, tuples and sequences by position, and anything else is deserialized from the only
+/// value present.
+pub (crate) struct NamedValuesDeserializer<'de> {
+    values: &'de [(String, String)]
+}
+
+impl <'de> NamedValuesDeserializer<'de> {
+    pub fn new(values: &'de [(String, String)]) -> Self {
+        NamedValuesDeserializer { values }
+    }
+
+    fn single_value(&self) -> Result<ValueDeserializer<'de>, Error> {
+        match self.values {
+            [(_, value)] => Ok(ValueDeserializer(value)),
+            _ => Err(de::Error::custom(format!("expected 1 value but got {}", self.values.len())))
+        }
+    }
+}
+
+macro_rules! forward_to_single_value {
+    ($($method:ident)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+                self.single_value()?.$method(visitor)
+            }
+        )*
+    }
+}
+
+impl <'de> Deserializer<'de> for NamedValuesDeserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_map(NamedValuesAccess { values: self.values.iter(), value: None })
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(self, _name: &'static str, _fields: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_seq(ValuesAccess { values: self.values.iter().map(|(_, v)| v.as_str()) })
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(self, _name: &'static str, _len: usize, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(self, name: &'static str, variants: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error> {
+        self.single_value()?.deserialize_enum(name, variants, visitor)
+    }
+
+    forward_to_single_value! {
+        deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64
+        deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64 deserialize_f32
+        deserialize_f64 deserialize_char deserialize_str deserialize_string deserialize_bytes
+        deserialize_byte_buf deserialize_option deserialize_unit deserialize_identifier
+        deserialize_ignored_any
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, Self::Error> {
+        self.single_value()?.deserialize_unit(visitor)
+    }
+}
+
+struct NamedValuesAccess<'de, I> {
+    values: I,
+    value: Option<&'de str>
+}
+
+impl <'de, I: Iterator<Item = &'de (String, String)>> MapAccess<'de> for NamedValuesAccess<'de, I> {
+    type Error = Error;
+
+    fn next_key_seed<K: de::DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error> {
+        match self.values.next() {
+            Some((key, value)) => {
+                self.value = Some(value);
+                seed.deserialize(key.as_str().into_deserializer()).map(Some)
+            },
+            None => Ok(None)
+        }
+    }
+
+    fn next_value_seed<V: de::DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Self::Error> {
+        let value = self.value.take().expect("next_value_seed called before next_key_seed");
+        seed.deserialize(ValueDeserializer(value))
+    }
+}
+
+struct ValuesAccess<I> {
+    values: I
+}
+
+impl <'de, I: Iterator<Item = &'de str>> SeqAccess<'de> for ValuesAccess<I> {
+    type Error = Error;
+
+    fn next_element_seed<T: de::DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error> {
+        match self.values.next() {
+            Some(value) => seed.deserialize(ValueDeserializer(value)).map(Some),
+            None => Ok(None)
+        }
+    }
+}
+
+/// Deserialize some type from a single string. Numbers and booleans are
+/// parsed from the string.
+pub (crate) struct ValueDeserializer<'de>(pub &'de str);
+
+macro_rules! deserialize_parsed {
+    ($($method:ident => $visit:ident,)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+                match self.0.parse() {
+                    Ok(val) => visitor.$visit(val),
+                    Err(e) => Err(de::Error::custom(format!("'{}' is not valid: {}", self.0, e)))
+                }
+            }
+        )*
+    }
+}
+
+impl <'de> Deserializer<'de> for ValueDeserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_borrowed_str(self.0)
+    }
+
+    deserialize_parsed! {
+        deserialize_bool => visit_bool,
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+        deserialize_char => visit_char,
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(self, _name: &'static str, _variants: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_enum(self.0.into_deserializer())
+    }
+
+    serde::forward_to_deserialize_any! {
+        str string bytes byte_buf unit unit_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+        i128 u128
+    }
+}
+
+/// Deserialize some type from a list of names, each of which may have been given several values
+/// (as in a query string). Structs and maps are deserialized by name. Several values for some name
+/// can be deserialized into a sequence; anything else expects exactly one value to be given.
+pub (crate) struct GroupedValuesDeserializer<'de> {
+    values: &'de [(String, Vec<String>)]
+}
+
+impl <'de> GroupedValuesDeserializer<'de> {
+    pub fn new(values: &'de [(String, Vec<String>)]) -> Self {
+        GroupedValuesDeserializer { values }
+    }
+}
+
+impl <'de> Deserializer<'de> for GroupedValuesDeserializer<'de> {
+    type Error = FieldError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_map(GroupedValuesAccess { values: self.values.iter(), value: None })
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct enum
+        identifier ignored_any
+    }
+}
+
+struct GroupedValuesAccess<'de, I> {
+    values: I,
+    value: Option<(&'de str, &'de [String])>
+}
+
+impl <'de, I: Iterator<Item = &'de (String, Vec<String>)>> MapAccess<'de> for GroupedValuesAccess<'de, I> {
+    type Error = FieldError;
+
+    fn next_key_seed<K: de::DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error> {
+        match self.values.next() {
+            Some((key, values)) => {
+                self.value = Some((key, values));
+                seed.deserialize(key.as_str().into_deserializer()).map(Some)
+            },
+            None => Ok(None)
+        }
+    }
+
+    fn next_value_seed<V: de::DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Self::Error> {
+        let (key, values) = self.value.take().expect("next_value_seed called before next_key_seed");
+        seed.deserialize(MultiValueDeserializer(values))
+            .map_err(|e| FieldError { field: Some(key.to_owned()), message: e.to_string() })
+    }
+}
+
+// Deserialize some type from the values given for a single name.
+struct MultiValueDeserializer<'de>(&'de [String]);
+
+impl <'de> MultiValueDeserializer<'de> {
+    fn single_value(&self) -> Result<ValueDeserializer<'de>, Error> {
+        match self.0 {
+            [value] => Ok(ValueDeserializer(value)),
+            _ => Err(de::Error::custom(format!("expected 1 value but got {}", self.0.len())))
+        }
+    }
+}
+
+impl <'de> Deserializer<'de> for MultiValueDeserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_seq(ValuesAccess { values: self.0.iter().map(|v| v.as_str()) })
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(self, _name: &'static str, _len: usize, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(self, name: &'static str, variants: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error> {
+        self.single_value()?.deserialize_enum(name, variants, visitor)
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, Self::Error> {
+        self.single_value()?.deserialize_unit(visitor)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(self, name: &'static str, fields: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error> {
+        self.single_value()?.deserialize_struct(name, fields, visitor)
+    }
+
+    forward_to_single_value! {
+        deserialize_any deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64
+        deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64 deserialize_f32
+        deserialize_f64 deserialize_char deserialize_str deserialize_string deserialize_bytes
+        deserialize_byte_buf deserialize_unit deserialize_identifier deserialize_map
+        deserialize_ignored_any
+    }
+}
+
+/// An error deserializing some named values, which records the name of the
+/// value that it relates to, if any.
+#[derive(Debug,Clone,PartialEq)]
+pub (crate) struct FieldError {
+    pub field: Option<String>,
+    pub message: String
+}
+
+impl std::fmt::Display for FieldError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match &self.field {
+            Some(field) => write!(f, "'{}': {}", field, self.message),
+            None => write!(f, "{}", self.message)
+        }
+    }
+}
+
+impl std::error::Error for FieldError {}
+
+impl de::Error for FieldError {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        FieldError { field: None, message: msg.to_string() }
+    }
+    fn missing_field(field: &'static str) -> Self {
+        FieldError { field: Some(field.to_owned()), message: "missing value".to_owned() }
+    }
+}
@@ -2,8 +2,8 @@
 use http::{ Request, Response, method::Method };
 use std::future::Future;
 use std::pin::Pin;
-use crate::api::{ ApiBody, ApiBodyInfo, ApiError };
-use crate::handler::{ HandlerParam, HandlerBody, request::AsyncReadBody };
+use crate::api::{ ApiBody, ApiBodyInfo, ApiError, ApiErrorInfo };
+use crate::handler::{ HandlerParam, HandlerBody, request::AsyncReadBody, param::HandlerParamInfo };
 use super::response::HandlerResponse;
 use super::to_async::ToAsync;
 
@@ -13,7 +13,9 @@ pub struct Handler {
     pub method: Method,
     pub handler: Box<dyn for<'a> Fn(Request<&'a mut dyn AsyncReadBody>) -> Fut<'a, Result<Response<Vec<u8>>,ApiError>> + Send + Sync>,
     pub request_type: ApiBodyInfo,
-    pub response_type: ApiBodyInfo
+    pub response_type: ApiBodyInfo,
+    pub errors: Vec<ApiErrorInfo>,
+    pub param_info: HandlerParamInfo
 }
 
 // A type alias for an overly complicated boxed Future type that can be sent across threads.
@@ -96,7 +98,14 @@ macro_rules! resolve_for_contexts {
                         })
                     }),
                     request_type: BodyParam::api_body_info(),
-                    response_type: <Output as HandlerResponse>::ResponseBody::api_body_info()
+                    response_type: <Output as HandlerResponse>::ResponseBody::api_body_info(),
+                    errors: <Output as HandlerResponse>::handler_errors(),
+                    param_info: {
+                        #[allow(unused_mut)]
+                        let mut info = HandlerParamInfo::default();
+                        $( $ctx::handler_param_info(&mut info); )*
+                        info
+                    }
                 }
             }
         }
@@ -150,7 +159,14 @@ macro_rules! resolve_for_contexts {
                         description: "No request body is expected".to_owned(),
                         ty: crate::api::ApiBodyType::Null
                     },
-                    response_type: <Output as HandlerResponse>::ResponseBody::api_body_info()
+                    response_type: <Output as HandlerResponse>::ResponseBody::api_body_info(),
+                    errors: <Output as HandlerResponse>::handler_errors(),
+                    param_info: {
+                        #[allow(unused_mut)]
+                        let mut info = HandlerParamInfo::default();
+                        $( $ctx::handler_param_info(&mut info); )*
+                        info
+                    }
                 }
             }
         }
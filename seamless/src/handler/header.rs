@@ -0,0 +1,290 @@
+use std::marker::PhantomData;
+use std::ops::{ Deref, DerefMut };
+use http::{ HeaderMap, Request, header };
+use async_trait::async_trait;
+use serde::{ de::DeserializeOwned };
+use crate::api::{ ApiBody, ApiBodyInfo, ApiError, HeaderParamInfo };
+use super::de::ValueDeserializer;
+use super::param::{ HandlerParam, HandlerParamInfo };
+
+/// Implement this on a type in order to use it to name the header that a [`Header`]
+/// param extracts.
+///
+/// # Example
+///
+/// ```
+/// # use seamless::handler::header::HeaderKey;
+/// struct RequestId;
+///
+/// impl HeaderKey for RequestId {
+///     const NAME: &'static str = "x-request-id";
+/// }
+/// ```
+pub trait HeaderKey {
+    /// The name of the header (header names are case insensitive).
+    const NAME: &'static str;
+}
+
+/// Ask for this in a handler in order to be given the value of the header named by `N`
+/// (see [`HeaderKey`]), deserialized into `T`. Like path parameters, numbers and booleans
+/// are parsed from the header value. The request fails with a `400` [`ApiError`] if the
+/// header is missing or can't be deserialized into `T`; ask for an `Option<Header<N, T>>`
+/// instead if the header is optional.
+///
+/// # Example
+///
+/// ```
+/// # use seamless::{ Api, handler::{ header::{ Header, HeaderKey }, response::ToJson } };
+/// # let mut api = Api::new();
+/// struct PageSize;
+/// impl HeaderKey for PageSize {
+///     const NAME: &'static str = "x-page-size";
+/// }
+///
+/// api.add("items")
+///    .handler(|size: Header<PageSize, usize>| ToJson(vec![0; size.into_inner()]));
+/// ```
+pub struct Header<N, T> {
+    value: T,
+    name: PhantomData<N>
+}
+
+impl <N, T> Header<N, T> {
+    /// Hand back the value of the header.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+#[async_trait]
+impl <N: HeaderKey, T: DeserializeOwned + ApiBody + Send> HandlerParam for Header<N, T> {
+    type Error = ApiError;
+    async fn handler_param(req: &Request<()>) -> Result<Self,Self::Error> {
+        let value = header_str(req.headers(), N::NAME)?;
+        T::deserialize(ValueDeserializer(value))
+            .map(|value| Header { value, name: PhantomData })
+            .map_err(|e| invalid_header(N::NAME, e))
+    }
+    fn handler_param_info(info: &mut HandlerParamInfo) {
+        add_header_info(info, N::NAME, T::api_body_info());
+    }
+}
+
+impl <N, T> Deref for Header<N, T> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl <N, T> DerefMut for Header<N, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
+
+/// Ask for this in a handler in order to be given every header in the request. This never fails.
+#[derive(Debug,Clone)]
+pub struct Headers(pub HeaderMap);
+
+#[async_trait]
+impl HandlerParam for Headers {
+    type Error = std::convert::Infallible;
+    async fn handler_param(req: &Request<()>) -> Result<Self,Self::Error> {
+        Ok(Headers(req.headers().clone()))
+    }
+}
+
+impl Deref for Headers {
+    type Target = HeaderMap;
+    fn deref(&self) -> &HeaderMap {
+        &self.0
+    }
+}
+
+/// Ask for this in a handler in order to be given the value of the `Content-Type` header.
+#[derive(Debug,Clone,PartialEq)]
+pub struct ContentType(pub String);
+
+impl ContentType {
+    /// The media type, without any parameters and in lowercase (for instance `text/html`
+    /// given `text/html; charset=utf-8`).
+    pub fn media_type(&self) -> String {
+        self.0.split(';').next().unwrap_or("").trim().to_ascii_lowercase()
+    }
+
+    /// The value of the media type parameter with the name given, if there is one
+    /// (for instance `utf-8` for the parameter `charset` given `text/html; charset=utf-8`).
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.0.split(';')
+            .skip(1)
+            .filter_map(|param| param.split_once('='))
+            .find(|(n, _)| n.trim().eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.trim().trim_matches('"'))
+    }
+}
+
+#[async_trait]
+impl HandlerParam for ContentType {
+    type Error = ApiError;
+    async fn handler_param(req: &Request<()>) -> Result<Self,Self::Error> {
+        header_str(req.headers(), header::CONTENT_TYPE.as_str()).map(|v| ContentType(v.to_owned()))
+    }
+    fn handler_param_info(info: &mut HandlerParamInfo) {
+        add_header_info(info, header::CONTENT_TYPE.as_str(), String::api_body_info());
+    }
+}
+
+/// Ask for this in a handler in order to be given the value of the `User-Agent` header.
+#[derive(Debug,Clone,PartialEq)]
+pub struct UserAgent(pub String);
+
+#[async_trait]
+impl HandlerParam for UserAgent {
+    type Error = ApiError;
+    async fn handler_param(req: &Request<()>) -> Result<Self,Self::Error> {
+        header_str(req.headers(), header::USER_AGENT.as_str()).map(|v| UserAgent(v.to_owned()))
+    }
+    fn handler_param_info(info: &mut HandlerParamInfo) {
+        add_header_info(info, header::USER_AGENT.as_str(), String::api_body_info());
+    }
+}
+
+/// Ask for this in a handler in order to be given the languages listed in the
+/// `Accept-Language` header, most preferred first. Languages that are given
+/// a quality of `0` (ie those which aren't acceptable) are left out.
+#[derive(Debug,Clone,PartialEq)]
+pub struct AcceptLanguage(pub Vec<String>);
+
+impl AcceptLanguage {
+    fn parse(value: &str) -> Option<AcceptLanguage> {
+        let mut languages = vec![];
+        for item in value.split(',').map(str::trim).filter(|item| !item.is_empty()) {
+            let mut parts = item.split(';');
+            let language = parts.next()?.trim();
+            let mut quality = 1.0;
+            for param in parts {
+                if let Some(("q", q)) = param.split_once('=').map(|(n, v)| (n.trim(), v.trim())) {
+                    quality = q.parse::<f32>().ok().filter(|q| (0.0..=1.0).contains(q))?;
+                }
+            }
+            if language.is_empty() {
+                return None
+            }
+            if quality > 0.0 {
+                languages.push((language.to_owned(), quality));
+            }
+        }
+        // This is a stable sort, so languages of equal quality stay in the order given:
+        languages.sort_by(|a, b| b.1.total_cmp(&a.1));
+        Some(AcceptLanguage(languages.into_iter().map(|(language, _)| language).collect()))
+    }
+}
+
+#[async_trait]
+impl HandlerParam for AcceptLanguage {
+    type Error = ApiError;
+    async fn handler_param(req: &Request<()>) -> Result<Self,Self::Error> {
+        let name = header::ACCEPT_LANGUAGE.as_str();
+        AcceptLanguage::parse(header_str(req.headers(), name)?)
+            .ok_or_else(|| invalid_header(name, "expected a list of languages"))
+    }
+    fn handler_param_info(info: &mut HandlerParamInfo) {
+        add_header_info(info, header::ACCEPT_LANGUAGE.as_str(), String::api_body_info());
+    }
+}
+
+/// An authentication scheme that the credentials in an `Authorization`
+/// header can be given in; see [`Authorization`].
+pub trait AuthScheme: Sized {
+    /// The name of the scheme (for instance `Bearer`), which is compared case insensitively.
+    const NAME: &'static str;
+    /// Parse the credentials that follow the scheme name, handing back `None` if they're not valid.
+    fn parse(credentials: &str) -> Option<Self>;
+}
+
+/// The `Bearer` authentication scheme, which consists of a single token.
+#[derive(Debug,Clone,PartialEq)]
+pub struct Bearer(pub String);
+
+impl AuthScheme for Bearer {
+    const NAME: &'static str = "Bearer";
+    fn parse(credentials: &str) -> Option<Self> {
+        let token = credentials.trim();
+        if token.is_empty() || token.contains(char::is_whitespace) {
+            None
+        } else {
+            Some(Bearer(token.to_owned()))
+        }
+    }
+}
+
+/// Ask for this in a handler in order to be given the credentials from the `Authorization`
+/// header, which are expected to use the scheme `S` (see [`AuthScheme`]).
+///
+/// # Example
+///
+/// ```
+/// # use seamless::{ Api, handler::{ header::{ Authorization, Bearer }, response::ToJson } };
+/// # let mut api = Api::new();
+/// api.add("me")
+///    .handler(|Authorization(Bearer(token))| ToJson(token));
+/// ```
+#[derive(Debug,Clone,PartialEq)]
+pub struct Authorization<S>(pub S);
+
+#[async_trait]
+impl <S: AuthScheme + Send> HandlerParam for Authorization<S> {
+    type Error = ApiError;
+    async fn handler_param(req: &Request<()>) -> Result<Self,Self::Error> {
+        let name = header::AUTHORIZATION.as_str();
+        let value = header_str(req.headers(), name)?;
+        let (scheme, credentials) = value.trim().split_once(' ').unwrap_or((value, ""));
+        if !scheme.eq_ignore_ascii_case(S::NAME) {
+            return Err(invalid_header(name, format!("expected the {} scheme", S::NAME)))
+        }
+        S::parse(credentials)
+            .map(Authorization)
+            .ok_or_else(|| invalid_header(name, format!("invalid {} credentials", S::NAME)))
+    }
+    fn handler_param_info(info: &mut HandlerParamInfo) {
+        add_header_info(info, header::AUTHORIZATION.as_str(), String::api_body_info());
+    }
+}
+
+// Get the value of a header as a string, failing if it's missing or not valid UTF-8.
+fn header_str<'a>(headers: &'a HeaderMap, name: &str) -> Result<&'a str, ApiError> {
+    let value = headers.get(name).ok_or_else(|| header_error(name, None))?;
+    value.to_str().map_err(|_| invalid_header(name, "not valid UTF-8"))
+}
+
+fn invalid_header(name: &str, reason: impl std::fmt::Display) -> ApiError {
+    header_error(name, Some(reason.to_string()))
+}
+
+// Header names are case insensitive, so we always refer to them in lowercase.
+fn header_error(name: &str, reason: Option<String>) -> ApiError {
+    let name = name.to_ascii_lowercase();
+    let msg = match reason {
+        Some(reason) => format!("Invalid header '{}': {}", name, reason),
+        None => format!("Missing header '{}'", name)
+    };
+    ApiError {
+        code: 400,
+        internal_message: msg.clone(),
+        external_message: msg,
+        value: Some(serde_json::json!({ "header": name })),
+        problem_type: None,
+        error_code: None
+    }
+}
+
+// Record that some header is required. If it's already been recorded, the existing entry is kept.
+fn add_header_info(info: &mut HandlerParamInfo, name: &str, ty: ApiBodyInfo) {
+    let name = name.to_ascii_lowercase();
+    match info.headers.iter_mut().find(|h| h.name == name) {
+        Some(existing) => existing.required = true,
+        None => info.headers.push(HeaderParamInfo { name, required: true, ty })
+    }
+}
+
@@ -1,8 +1,8 @@
 //! This module provides traits and structs that relate to the handler functions
 //! that we can pass to API routes.
-mod param;
 mod handler;
 mod to_async;
+mod de;
 
 /// This contains the [`HandlerBody`] trait, which you can implement on a type
 /// in order to allow it to be used at a parameter in a handler function which
@@ -15,6 +15,24 @@ pub mod body;
 /// return from handler functions.
 pub mod response;
 
+/// This contains the [`HandlerParam`] trait, which you can implement on a type in order
+/// to allow it to be used as a parameter in a handler function which extracts data from
+/// anything other than the request body. [`param::Path`] and [`param::Query`] are exposed,
+/// which implement this trait in order to hand back parameters captured from the route path
+/// and the query string respectively.
+pub mod param;
+
+/// This contains [`HandlerParam`] implementations which extract headers from the request. Use
+/// [`header::Header`] to extract any header, or one of the more specific types (like
+/// [`header::Authorization`]) to extract and parse some common headers. Missing or invalid
+/// headers lead to a `400` [`crate::api::ApiError`].
+pub mod header;
+
+/// This contains the [`cookie::Cookies`] param, which can be used to read the cookies sent with
+/// a request and to set cookies on the response. With the `secure-cookies` feature enabled,
+/// cookies can also be signed or encrypted.
+pub mod cookie;
+
 /// This contains helpers around the body that you'll need to provide as part
 /// of an [`http::Request`], mainly geared around allowing requests to be streamed
 /// in if desired.
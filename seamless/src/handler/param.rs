@@ -1,6 +1,9 @@
+use std::ops::{ Deref, DerefMut };
 use http::{ Request };
 use async_trait::async_trait;
-use crate::api::ApiError;
+use serde::{ de::DeserializeOwned };
+use crate::api::{ ApiBody, ApiBodyInfo, ApiError, HeaderParamInfo, percent_decode };
+use super::de::{ NamedValuesDeserializer, GroupedValuesDeserializer };
 
 /// Implement this for anything that you want to be able to pass into a request
 /// handler that doesn't want to consume the body of the request. This is
@@ -52,6 +55,34 @@ pub trait HandlerParam where Self: Sized {
     /// else return an error of type `E` describing what went wrong. Any errors
     /// here will lead to the route bailing out and the handler not being run.
     async fn handler_param(req: &Request<()>) -> Result<Self,Self::Error>;
+    /// Describe the parts of the request that this parameter expects to be present
+    /// (for instance, the shape of any path parameters). This information is made
+    /// available in [`crate::api::RouteInfo`]. By default, nothing is described.
+    fn handler_param_info(_info: &mut HandlerParamInfo) {}
+}
+
+/// Information about the parts of a request that the [`HandlerParam`]s given to
+/// some handler function expect to be present.
+#[derive(Debug,Clone,Default,PartialEq)]
+pub struct HandlerParamInfo {
+    /// The shape of the parameters expected in the route path; see [`Path`].
+    pub path: Option<ApiBodyInfo>,
+    /// The shape of the parameters expected in the query string; see [`Query`].
+    pub query: Option<ApiBodyInfo>,
+    /// The headers expected; see [`crate::handler::header`].
+    pub headers: Vec<HeaderParamInfo>
+}
+
+impl HandlerParamInfo {
+    // Describe some param whose failure doesn't prevent the handler from running,
+    // so any headers that it asks for are not required.
+    fn add_optional<T: HandlerParam>(&mut self) {
+        let len = self.headers.len();
+        T::handler_param_info(self);
+        for header in &mut self.headers[len..] {
+            header.required = false;
+        }
+    }
 }
 
 // Option<Body> means we'll return None to the handler if handler_param would fail.
@@ -62,6 +93,9 @@ impl <T: HandlerParam> HandlerParam for Option<T> {
     async fn handler_param(req: &Request<()>) -> Result<Self,Self::Error> {
         Ok(T::handler_param(req).await.ok())
     }
+    fn handler_param_info(info: &mut HandlerParamInfo) {
+        info.add_optional::<T>()
+    }
 }
 
 // Result<Context,Err> means we'll return the result of attempting to obtain the context.
@@ -72,4 +106,163 @@ impl <T: HandlerParam> HandlerParam for Result<T,<T as HandlerParam>::Error> {
     async fn handler_param(req: &Request<()>) -> Result<Self,Self::Error> {
         Ok(T::handler_param(req).await)
     }
+    fn handler_param_info(info: &mut HandlerParamInfo) {
+        info.add_optional::<T>()
+    }
+}
+
+/// Ask for this in a handler in order to be given the parameters captured from
+/// the route path. Route paths can contain parameters like `{id}` in place of any
+/// of their segments; each parameter captures the corresponding segment of the request
+/// path.
+///
+/// `T` can be a struct (or map) in order to obtain the parameters by name, a tuple
+/// in order to obtain them in the order that they appear in the route path, or any other
+/// type (like a `String` or number) if the route path has exactly one parameter.
+///
+/// # Example
+///
+/// ```
+/// # use seamless::{ Api, ApiBody, handler::{ param::Path, response::ToJson } };
+/// # let mut api = Api::new();
+/// #[ApiBody(Deserialize)]
+/// struct PostPath {
+///     user_id: usize,
+///     post: String
+/// }
+///
+/// api.add("users/{user_id}/posts/{post}")
+///    .handler(|Path(path): Path<PostPath>| ToJson(format!("{} by {}", path.post, path.user_id)));
+///
+/// api.add("users/{id}")
+///    .handler(|Path(id): Path<usize>| ToJson(id));
+/// ```
+pub struct Path<T>(pub T);
+
+#[async_trait]
+impl <T: DeserializeOwned + ApiBody + Send> HandlerParam for Path<T> {
+    type Error = ApiError;
+    async fn handler_param(req: &Request<()>) -> Result<Self,Self::Error> {
+        let params = req.extensions()
+            .get::<PathParams>()
+            .map(|p| &p.0[..])
+            .unwrap_or(&[]);
+        T::deserialize(NamedValuesDeserializer::new(params))
+            .map(Path)
+            .map_err(|e| ApiError {
+                code: 400,
+                internal_message: format!("Invalid path parameters: {}", e),
+                external_message: format!("Invalid path parameters: {}", e),
+                value: None,
+                problem_type: None,
+                error_code: None
+            })
+    }
+    fn handler_param_info(info: &mut HandlerParamInfo) {
+        info.path = Some(T::api_body_info());
+    }
+}
+
+impl <T> Deref for Path<T> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl <T> DerefMut for Path<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
 }
+
+/// Ask for this in a handler in order to be given the parameters in the query string
+/// of the request URL. This is useful for passing structured input to `GET` routes, which
+/// can't be given a request body.
+///
+/// `T` is expected to be a struct (or map), and each of the parameters in the query string
+/// is deserialized into the field with the same name. Parameters which are given several times
+/// (for instance `?tag=a&tag=b`) can be deserialized into a `Vec`, and any parameters that aren't
+/// expected are ignored. If the query string can't be deserialized into `T`, the request fails
+/// with a `400` [`ApiError`] which names the parameter that was at fault.
+///
+/// # Example
+///
+/// ```
+/// # use seamless::{ Api, ApiBody, handler::{ param::Query, response::ToJson } };
+/// # let mut api = Api::new();
+/// #[ApiBody(Deserialize)]
+/// struct Search {
+///     term: String,
+///     limit: Option<usize>,
+///     tag: Option<Vec<String>>
+/// }
+///
+/// // Handles requests like `/search?term=foo&tag=a&tag=b`:
+/// api.add("search")
+///    .handler(|Query(search): Query<Search>| ToJson(search.tag.unwrap_or_default()));
+/// ```
+pub struct Query<T>(pub T);
+
+#[async_trait]
+impl <T: DeserializeOwned + ApiBody + Send> HandlerParam for Query<T> {
+    type Error = ApiError;
+    async fn handler_param(req: &Request<()>) -> Result<Self,Self::Error> {
+        let query = req.uri().query().unwrap_or("");
+        let values = query_values(query).ok_or_else(|| ApiError {
+            code: 400,
+            internal_message: "Invalid query string".to_owned(),
+            external_message: "Invalid query string".to_owned(),
+            value: None,
+            problem_type: None,
+            error_code: None
+        })?;
+        T::deserialize(GroupedValuesDeserializer::new(&values))
+            .map(Query)
+            .map_err(|e| ApiError {
+                code: 400,
+                internal_message: format!("Invalid query parameters: {}", e),
+                external_message: format!("Invalid query parameters: {}", e),
+                value: e.field.map(|field| serde_json::json!({ "field": field })),
+                problem_type: None,
+                error_code: None
+            })
+    }
+    fn handler_param_info(info: &mut HandlerParamInfo) {
+        info.query = Some(T::api_body_info());
+    }
+}
+
+impl <T> Deref for Query<T> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl <T> DerefMut for Query<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+// Decode a query string into each of the names in it alongside the values given for
+// them, in the order that they first appear. Returns `None` if it can't be decoded.
+fn query_values(query: &str) -> Option<Vec<(String, Vec<String>)>> {
+    let mut values: Vec<(String, Vec<String>)> = vec![];
+    for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+        let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+        let name = percent_decode(&name.replace('+', " "))?;
+        let value = percent_decode(&value.replace('+', " "))?;
+        match values.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, existing)) => existing.push(value),
+            None => values.push((name, vec![value]))
+        }
+    }
+    Some(values)
+}
+
+// The API router places the parameters captured from the route path
+// into the request extensions, so that [`Path`] can get at them.
+#[derive(Debug,Clone,Default)]
+pub (crate) struct PathParams(pub Vec<(String,String)>);
@@ -1,4 +1,5 @@
-use crate::api::{ ApiBody, ApiBodyInfo, ApiError };
+use crate::api::{ ApiBody, ApiBodyInfo, ApiError, ApiErrorInfo, ApiErrors };
+use crate::api::merge_errors;
 use async_trait::async_trait;
 use serde::Serialize;
 
@@ -13,6 +14,11 @@ pub trait HandlerResponse {
     type ResponseBody: ApiBody;
     /// This describes how the type can be converted into an `http::Response`.
     async fn handler_response(self) -> Result<HttpResponse, ApiError>;
+    /// Describe the errors that [`HandlerResponse::handler_response()`] might hand back.
+    /// By default, no errors are described.
+    fn handler_errors() -> Vec<ApiErrorInfo> {
+        vec![]
+    }
 }
 
 /// Wrap responses in this to return them as JSON
@@ -48,19 +54,34 @@ where
         let res = self.ok_or_else(|| ApiError::path_not_found())?;
         res.handler_response().await.map_err(|e| e.into())
     }
+    fn handler_errors() -> Vec<ApiErrorInfo> {
+        let not_found = ApiError::path_not_found();
+        let not_found = ApiErrorInfo {
+            code: not_found.code,
+            external_message: Some(not_found.external_message),
+            value: None,
+            problem_type: None,
+            error_code: None
+        };
+        merge_errors(T::handler_errors(), vec![not_found])
+    }
 }
 
 // Results are valid HandlerResponse's if their T's are, and their E's convert to ApiError
+// (and can describe the errors they convert into)
 #[async_trait]
 impl <T, E> HandlerResponse for Result<T,E>
 where
     T: HandlerResponse + Send,
-    E: Into<ApiError> + Send + 'static,
+    E: Into<ApiError> + ApiErrors + Send + 'static,
 {
     type ResponseBody = <T as HandlerResponse>::ResponseBody;
     async fn handler_response(self) -> Result<HttpResponse, ApiError> {
         let res = self.map_err(|e| e.into())?;
         res.handler_response().await.map_err(|e| e.into())
     }
+    fn handler_errors() -> Vec<ApiErrorInfo> {
+        merge_errors(T::handler_errors(), E::api_error_info())
+    }
 }
 
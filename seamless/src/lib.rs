@@ -14,7 +14,7 @@ API and TypeScript-like clients without relying on external specifications like
 The typical steps for using this library are:
 
 - Annotate your input and output types for these routes with the [`macro@ApiBody`] macro.
-- Derive [`macro@ApiError`] (or manually implement `Into<ApiError>`) on any errors you wish to emit.
+- Derive [`macro@ApiError`] (or manually implement `Into<ApiError>` and [`api::ApiErrors`]) on any errors you wish to emit.
 - Declare each of your API routes using this library. API handlers can just ask for whatever they need as a
   function parameter, including arbitrary state or information based on the incoming request (you decide).
 - Once the API routes are declared, use [`Api::info()`] to obtain enough information about the API to
@@ -193,8 +193,10 @@ hand back to the user.
 For convenience, a [`handler::response::ToJson<T>`] type is provided that will encode the response as JSON.
 
 [`handler::HandlerResponse`] is also implemented for `Option`s and `Result`s, returning a 404 in the event that the
-`Option` is `None`, and returning the error from the `Result` (this must itself implement `Into<ApiError>`) in the
-event that the `Result` is `Err`.
+`Option` is `None`, and returning the error from the `Result` (this must itself implement `Into<ApiError>` and
+[`api::ApiErrors`]) in the event that the `Result` is `Err`. The errors that a route might hand back are described in
+[`api::RouteInfo::errors`]. Any others, such as errors from middleware, can be declared using
+[`api::RouteBuilder::errors()`].
 
 # Info
 
@@ -202,7 +204,8 @@ At some point, you'll probably want to get information about the shape of the AP
 and generate a typed API client (this is, after all, the main selling point of this library). To do this,
 use the [`Api::info()`] function.
 
-Probably the best way to see what shapes this info can take is by looking at `api/info.rs`.
+Probably the best way to see what shapes this info can take is by looking at `api/info.rs`. If you just want a
+TypeScript client, [`codegen::typescript::generate()`] will produce one from this info for you.
 
 Here's an example:
 
@@ -259,6 +262,7 @@ let info_json = json!([
         "name": "maths/divide",
         "description": "Divide two numbers by each other",
         "method": "POST",
+        "operation_id": "mathsDivide",
         "request_type": {
             "description": "Input consisting of two numbers",
             "shape": {
@@ -294,7 +298,10 @@ let info_json = json!([
                     }
                 }
             }
-        }
+        },
+        "errors": [
+            { "code": 400, "error_code": "maths.divide_by_zero" }
+        ]
     }
 ]);
 # assert_eq!(serde_json::to_value(info).unwrap(), info_json);
@@ -302,7 +309,7 @@ let info_json = json!([
 ```
 
 The "shape" object can have one of the following "type" literals: `String`, `Number`, `Boolean`, `Null`,
-`Any`, `ArrayOf`, `TupleOf`, `ObjectOf`, `Object`, `OneOf`, `StringLiteral`, `Optional`. Some of these will come
+`Any`, `ArrayOf`, `TupleOf`, `ObjectOf`, `Object`, `OneOf`, `StringLiteral`, `Optional`, `Ref`. Some of these will come
 with an additional perty. See `seamless/src/api/info.rs` to get a better feel for exactly what the possible responses
 can be.
 
@@ -332,6 +339,7 @@ type information being generated.
 
 pub mod handler;
 pub mod api;
+pub mod codegen;
 
 // Only exposed for seamless_macros; we point serde here. Doesn't need to be documented
 #[doc(hidden)]
@@ -36,4 +36,10 @@ fn compile_tests() {
     t.pass("tests/api_error_compile_tests/09_enum_toplevel_attrs.rs");
     t.pass("tests/api_error_compile_tests/10_enum_fields.rs");
     t.compile_fail("tests/api_error_compile_tests/11_enum_empty.rs");
+
+    // Values
+    t.compile_fail("tests/api_error_compile_tests/12_value_twice.rs");
+
+    // Problem types
+    t.compile_fail("tests/api_error_compile_tests/13_type_when_delegating.rs");
 }
@@ -379,4 +379,138 @@ fn flattens() {
         Foo{ hello: 10, another: Bar{ there: true, world: s("w") } },
     );
 
-}
\ No newline at end of file
+}
+
+#[test]
+fn converts_to_json_schema() {
+
+    #[ApiBody]
+    #[allow(dead_code)]
+    /// Foo comment
+    struct Foo {
+        /// Tuple comment
+        tuple: (usize, bool),
+        maybe: Option<String>,
+        kind: Kind
+    }
+
+    #[ApiBody]
+    #[allow(dead_code)]
+    enum Kind {
+        A,
+        B
+    }
+
+    assert_eq!(
+        Foo::api_body_info().to_json_schema(),
+        serde_json::json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "description": "Foo comment",
+            "type": "object",
+            "properties": {
+                "tuple": {
+                    "description": "Tuple comment",
+                    "type": "array",
+                    "prefixItems": [{ "type": "number" }, { "type": "boolean" }],
+                    "minItems": 2,
+                    "maxItems": 2
+                },
+                "maybe": {
+                    "anyOf": [{ "type": "string" }, { "type": "null" }]
+                },
+                "kind": {
+                    "oneOf": [
+                        { "type": "string", "const": "A" },
+                        { "type": "string", "const": "B" }
+                    ]
+                }
+            },
+            "required": ["kind", "tuple"]
+        })
+    );
+
+}
+
+#[test]
+fn recursive_types() {
+
+    #[ApiBody]
+    #[allow(dead_code)]
+    /// A node in a tree
+    struct TreeNode {
+        value: usize,
+        children: Vec<TreeNode>
+    }
+
+    // The recursive reference is not expanded:
+    assert_eq!(
+        TreeNode::api_body_info(),
+        ApiBodyInfo {
+            description: s("A node in a tree"),
+            ty: ApiBodyType::Object {
+                keys: map!{
+                    s("value") => ApiBodyInfo {
+                        description: s(""),
+                        ty: ApiBodyType::Number
+                    },
+                    s("children") => ApiBodyInfo {
+                        description: s(""),
+                        ty: ApiBodyType::ArrayOf {
+                            value: Box::new(ApiBodyInfo {
+                                description: s(""),
+                                ty: ApiBodyType::Ref { name: s("TreeNode") }
+                            })
+                        }
+                    }
+                }
+            }
+        }
+    );
+
+    #[ApiBody]
+    #[allow(dead_code)]
+    /// A comment
+    struct Comment {
+        text: String,
+        thread: Option<Thread>
+    }
+
+    #[ApiBody]
+    #[allow(dead_code)]
+    enum Thread {
+        Open { replies: Vec<Comment> },
+        Closed { reason: String }
+    }
+
+    // Mutually recursive types are fine too:
+    let (info, definitions) = Comment::api_body_info_with_definitions();
+    assert_eq!(info.ty, ApiBodyType::Ref { name: s("Comment") });
+    assert_eq!(definitions.keys().collect::<Vec<_>>(), vec!["Comment", "Thread"]);
+    assert_eq!(
+        definitions["Comment"].ty,
+        ApiBodyType::Object {
+            keys: map!{
+                s("text") => ApiBodyInfo {
+                    description: s(""),
+                    ty: ApiBodyType::String
+                },
+                s("thread") => ApiBodyInfo {
+                    description: s(""),
+                    ty: ApiBodyType::Optional {
+                        value: Box::new(ApiBodyInfo {
+                            description: s(""),
+                            ty: ApiBodyType::Ref { name: s("Thread") }
+                        })
+                    }
+                }
+            }
+        }
+    );
+
+    // Sanity check that serde is happy with the recursive type:
+    assert_eq!(
+        TreeNode { value: 1, children: vec![TreeNode { value: 2, children: vec![] }] }.to_json_value(),
+        serde_json::json!({ "value": 1, "children": [{ "value": 2, "children": [] }] })
+    );
+
+}
@@ -1,4 +1,5 @@
-use seamless::{ ApiError };
+use seamless::{ ApiError, api::{ ApiBody, ApiBodyType, ApiErrors, ApiErrorInfo } };
+use serde_json::json;
 
 #[derive(ApiError)]
 #[api_error(internal)]
@@ -78,4 +79,173 @@ fn test_internal_with_message_and_code() {
     assert_eq!(e.internal_message, "hi".to_owned());
     assert_eq!(e.external_message, "Not Authed".to_owned());
     assert_eq!(e.code, 400);
-}
\ No newline at end of file
+}
+
+#[derive(ApiError)]
+#[api_error(external, code = 403, type = "https://example.com/probs/out-of-credit")]
+struct WithProblemType;
+impl std::fmt::Display for WithProblemType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Out of credit")
+    }
+}
+
+#[test]
+fn test_problem_type() {
+    let e: ApiError = WithProblemType.into();
+    assert_eq!(e.external_message, "Out of credit".to_owned());
+    assert_eq!(e.code, 403);
+    assert_eq!(e.problem_type, Some("https://example.com/probs/out-of-credit".to_owned()));
+
+    let e: ApiError = InternalWithMsgAndCode { error: "hi".to_owned() }.into();
+    assert_eq!(e.problem_type, None);
+}
+
+#[derive(ApiError)]
+struct Delegated(WithProblemType);
+impl std::fmt::Display for Delegated {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[test]
+fn test_error_info() {
+    assert_eq!(InternalWithMsgAndCode::api_error_info(), vec![ApiErrorInfo {
+        code: 400,
+        external_message: Some("Not Authed".to_owned()),
+        value: None,
+        problem_type: None,
+        error_code: None
+    }]);
+    // External messages come from the Display impl, so we don't know them up front:
+    let out_of_credit = ApiErrorInfo {
+        code: 403,
+        external_message: None,
+        value: None,
+        problem_type: Some("https://example.com/probs/out-of-credit".to_owned()),
+        error_code: Some("with_problem_type".to_owned())
+    };
+    assert_eq!(WithProblemType::api_error_info(), vec![out_of_credit.clone()]);
+    assert_eq!(Delegated::api_error_info(), vec![out_of_credit]);
+}
+
+#[derive(ApiError)]
+#[api_error(external, code = 429)]
+struct RateLimited {
+    reason: String,
+    #[api_error(value)]
+    retry_after: u32
+}
+impl std::fmt::Display for RateLimited {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Rate limited: {}", self.reason)
+    }
+}
+
+#[derive(ApiError)]
+#[api_error(external = "Invalid input", code = 422, value)]
+struct Invalid {
+    field: String,
+    problems: Vec<String>
+}
+impl std::fmt::Display for Invalid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} is invalid", self.field)
+    }
+}
+
+#[derive(ApiError)]
+#[api_error(external, code = 409, value)]
+struct Conflict(u64);
+impl std::fmt::Display for Conflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Conflicts with {}", self.0)
+    }
+}
+
+#[test]
+fn test_value() {
+    let e: ApiError = RateLimited { reason: "too many requests".to_owned(), retry_after: 30 }.into();
+    assert_eq!(e.external_message, "Rate limited: too many requests".to_owned());
+    assert_eq!(e.value, Some(json!(30)));
+
+    let e: ApiError = Invalid { field: "name".to_owned(), problems: vec!["too short".to_owned()] }.into();
+    assert_eq!(e.internal_message, "name is invalid".to_owned());
+    assert_eq!(e.value, Some(json!({ "field": "name", "problems": ["too short"] })));
+
+    let e: ApiError = Conflict(3).into();
+    assert_eq!(e.value, Some(json!(3)));
+
+    let e: ApiError = InternalWithMsgAndCode { error: "hi".to_owned() }.into();
+    assert_eq!(e.value, None);
+}
+
+#[test]
+fn test_value_info() {
+    assert_eq!(RateLimited::api_error_info(), vec![ApiErrorInfo {
+        code: 429,
+        external_message: None,
+        value: Some(u32::api_body_info()),
+        problem_type: None,
+        error_code: Some("rate_limited".to_owned())
+    }]);
+    assert_eq!(Conflict::api_error_info()[0].value, Some(u64::api_body_info()));
+
+    let keys = match Invalid::api_error_info().remove(0).value.map(|v| v.ty) {
+        Some(ApiBodyType::Object { keys }) => keys,
+        other => panic!("expected an object, got {:?}", other)
+    };
+    assert_eq!(keys.len(), 2);
+    assert_eq!(keys["field"], String::api_body_info());
+    assert_eq!(keys["problems"], <Vec<String>>::api_body_info());
+}
+
+#[derive(ApiError)]
+#[api_error(external, code = 402, error_code = "billing.out_of_credit")]
+struct OutOfCreditError;
+impl std::fmt::Display for OutOfCreditError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Out of credit")
+    }
+}
+
+#[derive(ApiError)]
+#[api_error(external)]
+struct HTTPFailureError;
+impl std::fmt::Display for HTTPFailureError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "HTTP failure")
+    }
+}
+
+#[derive(ApiError)]
+#[api_error(internal, error_code = "upstream.timeout")]
+struct UpstreamTimeoutError;
+impl std::fmt::Display for UpstreamTimeoutError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Upstream timed out")
+    }
+}
+
+#[test]
+fn test_error_code() {
+    let e: ApiError = OutOfCreditError.into();
+    assert_eq!(e.error_code, Some("billing.out_of_credit".to_owned()));
+    assert_eq!(OutOfCreditError::api_error_info()[0].error_code, Some("billing.out_of_credit".to_owned()));
+
+    // Error codes of external errors are derived from the struct name if not given:
+    let e: ApiError = HTTPFailureError.into();
+    assert_eq!(e.error_code, Some("http_failure".to_owned()));
+
+    // Internal errors only have an error code if it's given, so as not to give them away:
+    let e: ApiError = Internal { error: "hi".to_owned() }.into();
+    assert_eq!(e.error_code, None);
+    assert_eq!(Internal::api_error_info()[0].error_code, None);
+    let e: ApiError = UpstreamTimeoutError.into();
+    assert_eq!(e.error_code, Some("upstream.timeout".to_owned()));
+
+    // Delegated errors use the error code of the error they delegate to:
+    let e: ApiError = Delegated(WithProblemType).into();
+    assert_eq!(e.error_code, Some("with_problem_type".to_owned()));
+}
@@ -1,4 +1,5 @@
-use seamless::{ ApiError };
+use seamless::{ ApiError, api::{ ApiBody, ApiErrors, ApiErrorInfo } };
+use serde_json::json;
 
 #[derive(ApiError)]
 #[api_error(internal)]
@@ -62,4 +63,143 @@ fn test_enum_delegated() {
     assert_eq!(a.code, 500);
     assert_eq!(a.internal_message, "bar".to_owned());
     assert_eq!(a.external_message, "bar".to_owned());
-}
\ No newline at end of file
+}
+
+#[derive(ApiError)]
+#[api_error(external, type = "https://example.com/probs/payment")]
+enum PaymentError {
+    Declined,
+    #[api_error(external, code = 403, type = "https://example.com/probs/out-of-credit")]
+    OutOfCredit,
+    #[api_error(inner)]
+    Other(Bar)
+}
+impl std::fmt::Display for PaymentError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "payment failed")
+    }
+}
+
+#[test]
+fn test_enum_problem_types() {
+    let a: ApiError = PaymentError::Declined.into();
+    assert_eq!(a.problem_type, Some("https://example.com/probs/payment".to_owned()));
+
+    let a: ApiError = PaymentError::OutOfCredit.into();
+    assert_eq!(a.code, 403);
+    assert_eq!(a.problem_type, Some("https://example.com/probs/out-of-credit".to_owned()));
+
+    // Delegated variants use the problem type of the error they delegate to:
+    let a: ApiError = PaymentError::Other(Bar).into();
+    assert_eq!(a.problem_type, None);
+
+    let a: ApiError = Foo::A.into();
+    assert_eq!(a.problem_type, None);
+}
+
+#[test]
+fn test_enum_error_info() {
+    let info = |code: u16, external_message: Option<&str>, error_code: Option<&str>| ApiErrorInfo {
+        code,
+        external_message: external_message.map(|m| m.to_owned()),
+        value: None,
+        problem_type: None,
+        error_code: error_code.map(|c| c.to_owned())
+    };
+    // Delegated variants are described by the type they delegate to, and
+    // only external variants are given error codes by default:
+    assert_eq!(Foo::api_error_info(), vec![
+        info(500, Some("Internal server error"), None),
+        info(404, Some("Hidden"), None),
+        info(500, None, Some("foo.c")),
+        info(500, None, Some("bar"))
+    ]);
+}
+
+#[derive(ApiError)]
+#[api_error(external, code = 409)]
+enum ConflictError {
+    #[api_error(value)]
+    Exists(u64),
+    Changed {
+        by: String,
+        #[api_error(value)]
+        version: u32
+    },
+    #[api_error(value)]
+    Moved(String, u64),
+    Unknown
+}
+impl std::fmt::Display for ConflictError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConflictError::Changed { by, .. } => write!(f, "changed by {}", by),
+            _ => write!(f, "conflict")
+        }
+    }
+}
+
+#[test]
+fn test_enum_values() {
+    let a: ApiError = ConflictError::Exists(3).into();
+    assert_eq!(a.value, Some(json!(3)));
+
+    let a: ApiError = ConflictError::Changed { by: "bob".to_owned(), version: 2 }.into();
+    assert_eq!(a.external_message, "changed by bob".to_owned());
+    assert_eq!(a.value, Some(json!(2)));
+
+    let a: ApiError = ConflictError::Moved("users".to_owned(), 4).into();
+    assert_eq!(a.value, Some(json!(["users", 4])));
+
+    let a: ApiError = ConflictError::Unknown.into();
+    assert_eq!(a.value, None);
+}
+
+#[test]
+fn test_enum_value_info() {
+    let values: Vec<_> = ConflictError::api_error_info().into_iter().map(|e| e.value).collect();
+    assert_eq!(values, vec![
+        Some(u64::api_body_info()),
+        Some(u32::api_body_info()),
+        Some(<(String, u64)>::api_body_info()),
+        None
+    ]);
+}
+
+#[derive(ApiError)]
+#[api_error(external, code = 400)]
+enum MathsError {
+    DivideByZero,
+    #[api_error(error_code = "maths.nan")]
+    NotANumber,
+    #[api_error(inner)]
+    Conflict(ConflictError)
+}
+impl std::fmt::Display for MathsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "maths failed")
+    }
+}
+
+#[test]
+fn test_enum_error_codes() {
+    // Error codes are derived from the enum and variant names if not given:
+    let a: ApiError = MathsError::DivideByZero.into();
+    assert_eq!(a.error_code, Some("maths.divide_by_zero".to_owned()));
+
+    let a: ApiError = MathsError::NotANumber.into();
+    assert_eq!(a.error_code, Some("maths.nan".to_owned()));
+
+    let a: ApiError = MathsError::Conflict(ConflictError::Unknown).into();
+    assert_eq!(a.error_code, Some("conflict.unknown".to_owned()));
+
+    let codes: Vec<_> = MathsError::api_error_info().into_iter().filter_map(|e| e.error_code).collect();
+    assert_eq!(codes, vec![
+        "maths.divide_by_zero",
+        "maths.nan",
+        "conflict.exists",
+        "conflict.changed",
+        "conflict.moved",
+        "conflict.unknown"
+    ]);
+}
@@ -0,0 +1,17 @@
+#[derive(seamless::ApiError)]
+#[api_error(external, code = 429)]
+struct Foo {
+    #[api_error(value)]
+    retry_after: u32,
+    #[api_error(value)]
+    limit: u32
+}
+
+// Normally we'd use thiserror or something:
+impl std::fmt::Display for Foo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Rate limited")
+    }
+}
+
+fn main() { }
@@ -0,0 +1,21 @@
+#[derive(seamless::ApiError)]
+#[api_error(external)]
+struct Inner;
+
+#[derive(seamless::ApiError)]
+#[api_error(type = "https://example.com/probs/inner")]
+struct Foo(Inner);
+
+// Normally we'd use thiserror or something:
+impl std::fmt::Display for Inner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Inner")
+    }
+}
+impl std::fmt::Display for Foo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+fn main() { }
@@ -0,0 +1,348 @@
+use pretty_assertions::{ assert_eq };
+use seamless::{
+    api::{ Api, ApiBody, ApiBodyInfo, ApiBodyType, ApiError, ApiErrorInfo, ApiErrors },
+    handler::{ body::FromJson, response::ToJson },
+};
+
+macro_rules! map {
+    ( $($key:expr => $val:expr),* ) => ({
+        let mut m = std::collections::HashMap::new();
+        $( m.insert($key, $val); )*
+        m
+    })
+}
+
+fn s(s: &str) -> String {
+    s.to_owned()
+}
+
+fn ref_to(name: &str) -> ApiBodyInfo {
+    ApiBodyInfo { description: s(""), ty: ApiBodyType::Ref { name: s(name) } }
+}
+
+/// A user
+#[ApiBody]
+struct User {
+    name: String,
+    /// The user's friend
+    friend: Option<Box<User2>>
+}
+
+/// Another user
+#[ApiBody]
+struct User2 {
+    name: String
+}
+
+mod other {
+    use seamless::ApiBody;
+
+    /// A different user
+    #[ApiBody]
+    pub struct User {
+        pub id: usize
+    }
+}
+
+fn user_api() -> Api {
+    let mut api = Api::new();
+    api.add("users/get")
+        .handler(|| ToJson(User { name: s("Bob"), friend: None }));
+    api.add("users/echo")
+        .handler(|FromJson(user): FromJson<User>| ToJson(user));
+    api.add("users/other")
+        .handler(|| ToJson(vec![other::User { id: 1 }]));
+    api
+}
+
+#[test]
+fn named_types_are_deduplicated() {
+    let info = user_api().info_with_definitions();
+
+    // Each named type is defined exactly once, and name clashes are avoided:
+    assert_eq!(
+        info.definitions.keys().collect::<Vec<_>>(),
+        vec!["User", "User2", "User3"]
+    );
+    assert_eq!(
+        info.definitions["User"],
+        ApiBodyInfo {
+            description: s("A user"),
+            ty: ApiBodyType::Object {
+                keys: map!{
+                    s("name") => ApiBodyInfo {
+                        description: s(""),
+                        ty: ApiBodyType::String
+                    },
+                    s("friend") => ApiBodyInfo {
+                        description: s("The user's friend"),
+                        ty: ApiBodyType::Optional { value: Box::new(ref_to("User2")) }
+                    }
+                }
+            }
+        }
+    );
+    assert_eq!(
+        info.definitions["User3"].description,
+        s("A different user")
+    );
+
+    // Routes refer to the named types rather than inlining them:
+    let routes: Vec<_> = info.routes.iter()
+        .map(|r| (r.name.as_str(), r.request_type.ty.clone(), r.response_type.clone()))
+        .collect();
+    assert_eq!(routes, vec![
+        ("users/echo", ref_to("User").ty, ref_to("User")),
+        ("users/get", ApiBodyType::Null, ref_to("User")),
+        ("users/other", ApiBodyType::Null, ApiBodyInfo {
+            description: s(""),
+            ty: ApiBodyType::ArrayOf { value: Box::new(ref_to("User3")) }
+        }),
+    ]);
+}
+
+#[test]
+fn info_inlines_named_types() {
+    let info = user_api().info();
+
+    // Asking for the info directly leads to the same output as calling `api_body_info`:
+    assert_eq!(info[0].name, "users/echo");
+    assert_eq!(info[0].request_type, User::api_body_info());
+    assert_eq!(info[0].response_type, User::api_body_info());
+    assert_eq!(info[2].response_type, <Vec<other::User>>::api_body_info());
+}
+
+/// A node in a tree
+#[ApiBody]
+struct TreeNode {
+    value: usize,
+    children: Vec<TreeNode>
+}
+
+#[test]
+fn recursive_types_can_be_used_in_routes() {
+    let mut api = Api::new();
+    api.add("tree/echo")
+        .handler(|FromJson(tree): FromJson<TreeNode>| ToJson(tree));
+
+    let info = api.info_with_definitions();
+    assert_eq!(info.routes[0].request_type, ref_to("TreeNode"));
+    assert_eq!(info.definitions.keys().collect::<Vec<_>>(), vec!["TreeNode"]);
+
+    // When inlining, the recursive reference is left alone:
+    assert_eq!(api.info()[0].request_type, TreeNode::api_body_info());
+}
+
+#[test]
+fn route_metadata_is_in_route_info() {
+    let mut api = Api::new();
+    api.add("users/list")
+        .summary("List users")
+        .description("List every user")
+        .tags(["users", "admin"])
+        .deprecated("use v2/users")
+        .operation_id("listUsers")
+        .meta("rate_limit", 10)
+        .meta("owner", "team-a")
+        .handler(|| ToJson(Vec::<String>::new()));
+    api.add("users/plain")
+        .handler(|| ToJson(1));
+
+    let info = api.info();
+    let route = &info[0];
+    assert_eq!(route.summary, "List users");
+    assert_eq!(route.tags, vec![s("users"), s("admin")]);
+    assert_eq!(route.deprecated, Some(s("use v2/users")));
+    assert_eq!(route.operation_id, "listUsers");
+    assert_eq!(serde_json::to_value(&route.meta).unwrap(), serde_json::json!({ "owner": "team-a", "rate_limit": 10 }));
+
+    // Unset metadata is left out when serialized:
+    let plain = serde_json::to_value(&info[1]).unwrap();
+    for key in ["summary", "tags", "deprecated", "meta"] {
+        assert!(plain.get(key).is_none(), "{} should not be serialized", key);
+    }
+}
+
+#[test]
+fn routes_sharing_a_path_are_all_in_route_info() {
+    let mut api = Api::new();
+    api.add("users")
+        .description("Add a user")
+        .handler(|_body: FromJson<String>| ToJson(1));
+    api.add("users")
+        .description("List users")
+        .handler(|| ToJson(Vec::<String>::new()));
+    api.add("users/list")
+        .operation_id("usersGet")
+        .handler(|| ToJson(Vec::<String>::new()));
+
+    let routes: Vec<_> = api.info().into_iter()
+        .map(|r| (r.method, r.name, r.description, r.operation_id))
+        .collect();
+    assert_eq!(routes, vec![
+        (s("GET"), s("users"), s("List users"), s("usersGet2")),
+        (s("POST"), s("users"), s("Add a user"), s("usersPost")),
+        (s("GET"), s("users/list"), s(""), s("usersGet")),
+    ]);
+}
+
+#[derive(ApiError, Debug, thiserror::Error)]
+#[error("Not allowed")]
+#[api_error(internal, code = 403, external = "Forbidden", type = "https://example.com/probs/forbidden", error_code = "forbidden")]
+struct Forbidden;
+
+#[derive(ApiError, Debug, thiserror::Error)]
+#[api_error(internal)]
+#[allow(dead_code)]
+enum UserError {
+    #[error("Database error")]
+    Database,
+    #[error("Cache error")]
+    #[api_error(error_code = "user.cache")]
+    Cache,
+    #[error("User {0} is invalid")]
+    #[api_error(external, code = 400)]
+    Invalid(usize),
+    #[error("forbidden")]
+    #[api_error(inner)]
+    Forbidden(Forbidden)
+}
+
+#[test]
+fn route_errors_are_in_route_info() {
+    let mut api = Api::new();
+    api.add("users/find")
+        .handler(|| -> Result<Option<ToJson<usize>>, UserError> { Ok(None) });
+    api.add("users/forbidden")
+        .handler(|| Err::<ToJson<()>, _>(Forbidden));
+    api.add("users/any")
+        .handler(|| Err::<ToJson<()>, _>(ApiError::path_not_found()));
+
+    let error = |code: u16, external_message: Option<&str>, problem_type: Option<&str>, error_code: Option<&str>| ApiErrorInfo {
+        code,
+        external_message: external_message.map(s),
+        value: None,
+        problem_type: problem_type.map(s),
+        error_code: error_code.map(s)
+    };
+    let errors = |name: &str| api.info().into_iter().find(|r| r.name == name).unwrap().errors;
+
+    // Errors are told apart by their error codes, even if they have the same message. Internal
+    // errors only have error codes if they're given:
+    assert_eq!(errors("users/find"), vec![
+        error(404, Some("Not found"), None, None),
+        error(500, Some("Internal server error"), None, None),
+        error(500, Some("Internal server error"), None, Some("user.cache")),
+        error(400, None, None, Some("user.invalid")),
+        error(403, Some("Forbidden"), Some("https://example.com/probs/forbidden"), Some("forbidden")),
+    ]);
+    assert_eq!(errors("users/forbidden"), vec![
+        error(403, Some("Forbidden"), Some("https://example.com/probs/forbidden"), Some("forbidden")),
+    ]);
+    // We don't know which errors a plain `ApiError` might be:
+    assert_eq!(errors("users/any"), vec![]);
+}
+
+#[test]
+fn extra_route_errors_can_be_declared() {
+    let mut api = Api::new();
+    api.add("users/find")
+        .errors::<UserError>()
+        .handler(|| -> Result<Option<ToJson<usize>>, Forbidden> { Ok(None) });
+
+    let codes: Vec<_> = api.info()[0].errors.iter()
+        .map(|e| (e.code, e.error_code.clone()))
+        .collect();
+    assert_eq!(codes, vec![
+        (404, None),
+        (403, Some(s("forbidden"))),
+        (500, None),
+        (500, Some(s("user.cache"))),
+        (400, Some(s("user.invalid"))),
+    ]);
+}
+
+// An error that converts into an ApiError manually, and so describes no errors:
+#[derive(Debug)]
+struct NotLoggedIn;
+
+impl From<NotLoggedIn> for ApiError {
+    fn from(_: NotLoggedIn) -> ApiError {
+        ApiError::not_authorized("not logged in")
+    }
+}
+
+impl ApiErrors for NotLoggedIn {}
+
+// An error that converts into an ApiError but doesn't implement ApiErrors at all:
+#[derive(Debug)]
+struct NoSession;
+
+impl From<NoSession> for ApiError {
+    fn from(_: NoSession) -> ApiError {
+        ApiError::not_authorized("no session")
+    }
+}
+
+#[derive(ApiError, Debug, thiserror::Error)]
+#[allow(dead_code)]
+enum SessionError {
+    #[error("Session expired")]
+    #[api_error(external, code = 401)]
+    Expired,
+    #[error("no session")]
+    #[api_error(inner)]
+    NoSession(NoSession)
+}
+
+#[test]
+fn manually_converted_errors_describe_nothing() {
+    let mut api = Api::new();
+    api.add("users/me")
+        .handler(|| Err::<ToJson<()>, _>(NotLoggedIn));
+    api.add("users/session")
+        .handler(|| Err::<ToJson<()>, _>(SessionError::NoSession(NoSession)));
+
+    assert_eq!(api.info()[0].errors, vec![]);
+    // Inner errors that don't describe themselves add nothing to the errors described:
+    assert_eq!(api.info()[1].errors, vec![ApiErrorInfo {
+        code: 401,
+        external_message: None,
+        value: None,
+        problem_type: None,
+        error_code: Some(s("session.expired"))
+    }]);
+}
+
+/// Why a user is invalid
+#[ApiBody]
+#[derive(Debug)]
+struct Invalid {
+    problems: Vec<String>
+}
+
+#[derive(ApiError, Debug, thiserror::Error)]
+#[error("{0} is invalid")]
+#[api_error(external = "Invalid user", code = 422)]
+struct InvalidUser(String, #[api_error(value)] Invalid);
+
+#[test]
+fn route_error_values_are_in_route_info() {
+    let mut api = Api::new();
+    api.add("users/check")
+        .handler(|| Err::<ToJson<()>, _>(InvalidUser(s("bob"), Invalid { problems: vec![] })));
+
+    let error = ApiErrorInfo {
+        code: 422,
+        external_message: Some(s("Invalid user")),
+        value: Some(Invalid::api_body_info()),
+        problem_type: None,
+        error_code: None
+    };
+    assert_eq!(api.info()[0].errors, vec![error.clone()]);
+
+    // Named types are referred to by name when definitions are handed back:
+    let info = api.info_with_definitions();
+    assert_eq!(info.routes[0].errors, vec![ApiErrorInfo { value: Some(ref_to("Invalid")), ..error }]);
+    assert!(info.definitions.contains_key("Invalid"));
+}
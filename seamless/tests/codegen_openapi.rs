@@ -0,0 +1,312 @@
+use pretty_assertions::{ assert_eq };
+use seamless::{
+    api::{ Api, ApiBody, HeaderVersion, PathVersion },
+    handler::{ body::{ FromJson, FromBinary }, param::{ Path, Query }, header::{ Header, HeaderKey, Authorization, Bearer }, response::ToJson },
+};
+use serde_json::json;
+
+/// Input consisting of two numbers
+#[ApiBody]
+struct BinaryInput {
+    a: usize,
+    /// Input 'b'
+    b: Option<usize>
+}
+
+#[ApiBody]
+enum StatusValue {
+    Ok,
+    NotOk
+}
+
+#[test]
+fn generates_openapi_document() {
+    let mut api = Api::new_with_base_path("/api/");
+    api.add("maths/add")
+        .description("Add two numbers")
+        .handler(|FromJson(body): FromJson<BinaryInput>| ToJson(body.a + body.b.unwrap_or(0)));
+    api.add("meta/status")
+        .handler(|| ToJson(StatusValue::Ok));
+    api.add("upload")
+        .handler(|body: FromBinary| ToJson(std::collections::HashMap::from([("len".to_owned(), (body.len(), true))])));
+
+    let error_response = json!({
+        "description": "Error",
+        "content": {
+            "application/json": {
+                "schema": { "$ref": "#/components/schemas/ApiError" }
+            }
+        }
+    });
+
+    assert_eq!(api.openapi(), json!({
+        "openapi": "3.1.0",
+        "info": {
+            "title": "API",
+            "version": "0.0.0"
+        },
+        "paths": {
+            "/api/maths/add": {
+                "post": {
+                    "operationId": "mathsAdd",
+                    "description": "Add two numbers",
+                    "requestBody": {
+                        "required": true,
+                        "content": {
+                            "application/json": {
+                                "schema": { "$ref": "#/components/schemas/BinaryInput" }
+                            }
+                        }
+                    },
+                    "responses": {
+                        "200": {
+                            "description": "Success",
+                            "content": {
+                                "application/json": { "schema": { "type": "number" } }
+                            }
+                        },
+                        "default": error_response
+                    }
+                }
+            },
+            "/api/meta/status": {
+                "get": {
+                    "operationId": "metaStatus",
+                    "responses": {
+                        "200": {
+                            "description": "Success",
+                            "content": {
+                                "application/json": {
+                                    "schema": { "$ref": "#/components/schemas/StatusValue" }
+                                }
+                            }
+                        },
+                        "default": error_response
+                    }
+                }
+            },
+            "/api/upload": {
+                "post": {
+                    "operationId": "upload",
+                    "requestBody": {
+                        "required": true,
+                        "content": {
+                            "application/octet-stream": {
+                                "schema": { "description": "Binary data" }
+                            }
+                        }
+                    },
+                    "responses": {
+                        "200": {
+                            "description": "Success",
+                            "content": {
+                                "application/json": {
+                                    "schema": {
+                                        "type": "object",
+                                        "additionalProperties": {
+                                            "type": "array",
+                                            "prefixItems": [{ "type": "number" }, { "type": "boolean" }],
+                                            "minItems": 2,
+                                            "maxItems": 2
+                                        }
+                                    }
+                                }
+                            }
+                        },
+                        "default": error_response
+                    }
+                }
+            }
+        },
+        "components": {
+            "schemas": {
+                "ApiError": {
+                    "description": "An error returned from the API",
+                    "type": "object",
+                    "properties": {
+                        "code": {
+                            "description": "The HTTP status code",
+                            "type": "number"
+                        },
+                        "error_code": {
+                            "description": "A code identifying the error, or null if there is none",
+                            "oneOf": [{ "type": "string" }, { "type": "null" }]
+                        },
+                        "external_message": {
+                            "description": "A message describing the error",
+                            "type": "string"
+                        },
+                        "value": {
+                            "description": "Additional context about the error, or null if there is none"
+                        }
+                    },
+                    "required": ["code", "error_code", "external_message", "value"]
+                },
+                "BinaryInput": {
+                    "description": "Input consisting of two numbers",
+                    "type": "object",
+                    "properties": {
+                        "a": { "type": "number" },
+                        "b": {
+                            "description": "Input 'b'",
+                            "anyOf": [{ "type": "number" }, { "type": "null" }]
+                        }
+                    },
+                    "required": ["a"]
+                },
+                "StatusValue": {
+                    "oneOf": [
+                        { "type": "string", "const": "Ok" },
+                        { "type": "string", "const": "NotOk" }
+                    ]
+                }
+            }
+        }
+    }));
+}
+
+#[ApiBody(Deserialize)]
+#[allow(dead_code)]
+struct Search {
+    /// The term to search for
+    term: String,
+    tag: Option<Vec<String>>
+}
+
+#[test]
+fn describes_query_parameters() {
+    let mut api = Api::new();
+    api.add("users/{id}/posts")
+        .handler(|Path(id): Path<usize>, Query(search): Query<Search>| ToJson(format!("{}: {}", id, search.term)));
+
+    assert_eq!(api.openapi()["paths"]["/users/{id}/posts"]["get"]["parameters"], json!([
+        { "name": "id", "in": "path", "required": true, "schema": { "type": "number" } },
+        { "name": "tag", "in": "query", "required": false, "schema": { "type": "array", "items": { "type": "string" } } },
+        {
+            "name": "term",
+            "in": "query",
+            "required": true,
+            "description": "The term to search for",
+            "schema": { "type": "string", "description": "The term to search for" }
+        }
+    ]));
+}
+
+struct RequestId;
+impl HeaderKey for RequestId {
+    const NAME: &'static str = "x-request-id";
+}
+
+#[test]
+fn describes_header_parameters() {
+    let mut api = Api::new();
+    api.add("me")
+        .handler(|Authorization(Bearer(token)), _id: Option<Header<RequestId, usize>>| ToJson(token));
+
+    // Authorization headers can't be described as parameters:
+    assert_eq!(api.openapi()["paths"]["/me"]["get"]["parameters"], json!([
+        { "name": "x-request-id", "in": "header", "required": false, "schema": { "type": "number" } }
+    ]));
+}
+
+#[test]
+fn describes_path_parameters() {
+    let mut api = Api::new();
+    api.add("users/{id}/posts/{post}")
+        .handler(|Path((id, post)): Path<(usize, String)>| ToJson(format!("{}: {}", id, post)));
+
+    assert_eq!(api.openapi()["paths"]["/users/{id}/posts/{post}"]["get"]["parameters"], json!([
+        { "name": "id", "in": "path", "required": true, "schema": { "type": "number" } },
+        { "name": "post", "in": "path", "required": true, "schema": { "type": "string" } }
+    ]));
+}
+
+#[test]
+fn describes_groups_as_tags() {
+    let mut api = Api::new();
+    api.group("admin", |admin| {
+        admin.description("Admin routes");
+        admin.add("users").handler(|| ToJson(1));
+        admin.group("reports", |reports| {
+            reports.add("daily").handler(|| ToJson(2));
+        });
+    });
+    api.add("status").handler(|| ToJson(3));
+
+    let doc = api.openapi();
+    assert_eq!(doc["paths"]["/admin/users"]["get"]["tags"], json!(["admin"]));
+    assert_eq!(doc["paths"]["/admin/reports/daily"]["get"]["tags"], json!(["admin", "reports"]));
+    assert_eq!(doc["paths"]["/status"]["get"].get("tags"), None);
+    assert_eq!(doc["tags"], json!([
+        { "name": "admin", "description": "Admin routes" },
+        { "name": "reports" }
+    ]));
+}
+
+#[test]
+fn describes_route_metadata() {
+    let mut api = Api::new();
+    api.group("admin", |admin| {
+        admin.add("users")
+            .summary("List users")
+            .description("Every user")
+            .tags(["users", "admin"])
+            .deprecated("use v2/users")
+            .operation_id("listUsers")
+            .meta("rate-limit", 10)
+            .meta("x-owner", "team-a")
+            .handler(|| ToJson(1));
+    });
+
+    let op = &api.openapi()["paths"]["/admin/users"]["get"];
+    assert_eq!(op["tags"], json!(["admin", "users"]));
+    assert_eq!(op["summary"], json!("List users"));
+    assert_eq!(op["description"], json!("Every user\n\nDeprecated: use v2/users"));
+    assert_eq!(op["deprecated"], json!(true));
+    assert_eq!(op["operationId"], json!("listUsers"));
+    assert_eq!(op["x-rate-limit"], json!(10));
+    assert_eq!(op["x-owner"], json!("team-a"));
+}
+
+fn versioned_api() -> Api {
+    let mut api = Api::new_with_base_path("api");
+    api.add("users").version("v1").handler(|| ToJson("v1 users"));
+    api.add("users").version("v2").handler(|| ToJson(vec!["v2 users"]));
+    api.add("status").handler(|| ToJson("ok"));
+    api
+}
+
+#[test]
+fn describes_versions_in_paths() {
+    let mut api = versioned_api();
+    api.versioning(PathVersion);
+
+    let doc = api.openapi();
+    let mut paths: Vec<_> = doc["paths"].as_object().unwrap().keys().cloned().collect();
+    paths.sort();
+    assert_eq!(paths, vec!["/api/status", "/api/v1/users", "/api/v2/users"]);
+    assert_eq!(doc["paths"]["/api/v2/users"]["get"]["responses"]["200"]["content"]["application/json"]["schema"]["type"], json!("array"));
+
+    let doc = api.openapi_for_version("v1");
+    let mut paths: Vec<_> = doc["paths"].as_object().unwrap().keys().cloned().collect();
+    paths.sort();
+    assert_eq!(paths, vec!["/api/v1/status", "/api/v1/users"]);
+    assert_eq!(doc["paths"]["/api/v1/users"]["get"]["responses"]["200"]["content"]["application/json"]["schema"]["type"], json!("string"));
+}
+
+#[test]
+fn describes_one_version_at_a_time_if_not_in_paths() {
+    let mut api = versioned_api();
+    api.versioning(HeaderVersion::default());
+
+    // Versioned routes can't be told apart by path, so they're left out:
+    let doc = api.openapi();
+    let paths: Vec<_> = doc["paths"].as_object().unwrap().keys().cloned().collect();
+    assert_eq!(paths, vec!["/api/status"]);
+
+    let doc = api.openapi_for_version("v2");
+    let mut paths: Vec<_> = doc["paths"].as_object().unwrap().keys().cloned().collect();
+    paths.sort();
+    assert_eq!(paths, vec!["/api/status", "/api/users"]);
+    assert_eq!(doc["paths"]["/api/users"]["get"]["responses"]["200"]["content"]["application/json"]["schema"]["type"], json!("array"));
+}
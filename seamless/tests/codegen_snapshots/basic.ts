@@ -0,0 +1,135 @@
+// This file was generated by seamless. Do not edit it by hand.
+
+/** Input consisting of two numbers */
+export interface BinaryInput {
+    /** Input 'a' */
+    a: number;
+    /** Input 'b' */
+    b: number;
+}
+
+/** Output containing the original input and result */
+export interface BinaryOutput {
+    a: number;
+    b: number;
+    /** The result */
+    result: number;
+}
+
+/** The API status */
+export interface Status {
+    /** Some optional notes */
+    notes?: string[];
+    status: StatusValue;
+}
+
+export type StatusValue = "Ok" | "NotOk";
+
+export type BasicEchoRequest = string;
+
+export type BasicEchoResponse = string;
+
+export type BasicReverseRequest = number[];
+
+export type BasicReverseResponse = number[];
+
+export type MathsDivideRequest = BinaryInput;
+
+export type MathsDivideResponse = BinaryOutput;
+
+/** The errors that the route might respond with. */
+export type MathsDivideError =
+    | { code: 400; error_code: "maths.divide_by_zero"; external_message: string; value: null };
+
+export type MetaStatusResponse = Status;
+
+/** The errors that the route might respond with. */
+export type MetaStatusError =
+    | { code: 404; error_code: null; external_message: "Not found"; value: null };
+
+/**
+ * The error thrown when an API route responds with an unsuccessful status code. Client functions
+ * document the type of `body` for the errors that their route is known to respond with.
+ */
+export class ApiRequestError<Body = unknown> extends Error {
+    constructor(public readonly status: number, public readonly body: Body) {
+        super(`Request failed with status ${status}`);
+    }
+}
+
+/** Options to configure the API client with. */
+export interface ClientOptions {
+    /** The URL that route paths are relative to, eg "https://example.com/api". */
+    baseUrl?: string;
+    /** Headers to send along with every request. */
+    headers?: Record<string, string>;
+    /** The `fetch` implementation to use. Defaults to the global `fetch`. */
+    fetch?: typeof fetch;
+}
+
+type RequestKind = "none" | "json" | "binary";
+type ResponseKind = "json" | "binary";
+
+/** Create a client which can make requests to each of the API routes. */
+export function createClient(options: ClientOptions = {}) {
+    const baseUrl = (options.baseUrl ?? "").replace(/\/+$/, "");
+    const doFetch = options.fetch ?? fetch;
+
+    function withQuery(path: string, query: object): string {
+        const params = new URLSearchParams();
+        for (const [key, value] of Object.entries(query)) {
+            for (const v of Array.isArray(value) ? value : [value]) {
+                if (v !== undefined && v !== null) params.append(key, String(v));
+            }
+        }
+        const queryString = params.toString();
+        return queryString ? `${path}?${queryString}` : path;
+    }
+
+    async function call(method: string, path: string, requestKind: RequestKind, responseKind: ResponseKind, body?: unknown, routeHeaders: object = {}): Promise<any> {
+        const headers: Record<string, string> = { ...options.headers };
+        for (const [key, value] of Object.entries(routeHeaders)) {
+            if (value !== undefined && value !== null) headers[key] = String(value);
+        }
+        let requestBody: BodyInit | undefined = undefined;
+        if (requestKind === "json") {
+            headers["content-type"] = "application/json";
+            requestBody = JSON.stringify(body);
+        } else if (requestKind === "binary") {
+            headers["content-type"] = "application/octet-stream";
+            requestBody = body as Blob;
+        }
+
+        const res = await doFetch(`${baseUrl}/${path}`, { method, headers, body: requestBody });
+        if (!res.ok) {
+            const text = await res.text();
+            let errorBody: unknown = text;
+            try { errorBody = JSON.parse(text); } catch {}
+            throw new ApiRequestError(res.status, errorBody);
+        }
+        return responseKind === "binary" ? res.blob() : res.json();
+    }
+
+    return {
+        /** Echoes back a JSON string */
+        basicEcho: (body: BasicEchoRequest): Promise<BasicEchoResponse> =>
+            call("POST", "basic/echo", "json", "json", body),
+        /** Reverse an array of numbers */
+        basicReverse: (body: BasicReverseRequest): Promise<BasicReverseResponse> =>
+            call("POST", "basic/reverse", "json", "json", body),
+        /**
+         * Divide two numbers by each other
+         *
+         * @throws {ApiRequestError<MathsDivideError>}
+         */
+        mathsDivide: (body: MathsDivideRequest): Promise<MathsDivideResponse> =>
+            call("POST", "maths/divide", "json", "json", body),
+        /**
+         * Get the current API status
+         *
+         * @throws {ApiRequestError<MetaStatusError>}
+         */
+        metaStatus: (): Promise<MetaStatusResponse> =>
+            call("GET", "meta/status", "none", "json"),
+    };
+}
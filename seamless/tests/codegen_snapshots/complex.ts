@@ -0,0 +1,132 @@
+// This file was generated by seamless. Do not edit it by hand.
+
+/** Some shape */
+export type Shape = {
+    /** Variant tag */
+    kind: "Circle";
+    radius: number;
+} | {
+    height: number;
+    /** Variant tag */
+    kind: "Rect";
+    width: number;
+};
+
+/** Some shapes and things */
+export interface Shapes {
+    lookup: { [key: string]: [string, boolean] };
+    /** The "Raw" JSON */
+    raw: any;
+    shapes: Shape[];
+}
+
+export interface FilesPathParams {
+    path: string;
+}
+
+export type FilesPathResponse = string;
+
+export type ShapesEchoRequest = Shapes;
+
+export type ShapesEchoResponse = Shapes;
+
+export interface ShapesIdParams {
+    id: number;
+}
+
+export type ShapesIdResponse = number;
+
+export interface ShapesKindIdxUpdateParams {
+    idx: number;
+    kind: string;
+}
+
+export type ShapesKindIdxUpdateRequest = Shape;
+
+export type ShapesKindIdxUpdateResponse = [[string, number], Shape];
+
+/** Binary data */
+export type UploadRequest = Blob;
+
+export type UploadResponse = number;
+
+/**
+ * The error thrown when an API route responds with an unsuccessful status code. Client functions
+ * document the type of `body` for the errors that their route is known to respond with.
+ */
+export class ApiRequestError<Body = unknown> extends Error {
+    constructor(public readonly status: number, public readonly body: Body) {
+        super(`Request failed with status ${status}`);
+    }
+}
+
+/** Options to configure the API client with. */
+export interface ClientOptions {
+    /** The URL that route paths are relative to, eg "https://example.com/api". */
+    baseUrl?: string;
+    /** Headers to send along with every request. */
+    headers?: Record<string, string>;
+    /** The `fetch` implementation to use. Defaults to the global `fetch`. */
+    fetch?: typeof fetch;
+}
+
+type RequestKind = "none" | "json" | "binary";
+type ResponseKind = "json" | "binary";
+
+/** Create a client which can make requests to each of the API routes. */
+export function createClient(options: ClientOptions = {}) {
+    const baseUrl = (options.baseUrl ?? "").replace(/\/+$/, "");
+    const doFetch = options.fetch ?? fetch;
+
+    function withQuery(path: string, query: object): string {
+        const params = new URLSearchParams();
+        for (const [key, value] of Object.entries(query)) {
+            for (const v of Array.isArray(value) ? value : [value]) {
+                if (v !== undefined && v !== null) params.append(key, String(v));
+            }
+        }
+        const queryString = params.toString();
+        return queryString ? `${path}?${queryString}` : path;
+    }
+
+    async function call(method: string, path: string, requestKind: RequestKind, responseKind: ResponseKind, body?: unknown, routeHeaders: object = {}): Promise<any> {
+        const headers: Record<string, string> = { ...options.headers };
+        for (const [key, value] of Object.entries(routeHeaders)) {
+            if (value !== undefined && value !== null) headers[key] = String(value);
+        }
+        let requestBody: BodyInit | undefined = undefined;
+        if (requestKind === "json") {
+            headers["content-type"] = "application/json";
+            requestBody = JSON.stringify(body);
+        } else if (requestKind === "binary") {
+            headers["content-type"] = "application/octet-stream";
+            requestBody = body as Blob;
+        }
+
+        const res = await doFetch(`${baseUrl}/${path}`, { method, headers, body: requestBody });
+        if (!res.ok) {
+            const text = await res.text();
+            let errorBody: unknown = text;
+            try { errorBody = JSON.parse(text); } catch {}
+            throw new ApiRequestError(res.status, errorBody);
+        }
+        return responseKind === "binary" ? res.blob() : res.json();
+    }
+
+    return {
+        filesPath: (params: FilesPathParams): Promise<FilesPathResponse> =>
+            call("GET", `files/${String(params.path).split("/").map(encodeURIComponent).join("/")}`, "none", "json"),
+        /**
+         * Echo some shapes back
+         * across two lines
+         */
+        shapesEcho: (body: ShapesEchoRequest): Promise<ShapesEchoResponse> =>
+            call("POST", "shapes.echo", "json", "json", body),
+        shapesId: (params: ShapesIdParams): Promise<ShapesIdResponse> =>
+            call("GET", `shapes/${encodeURIComponent(String(params.id))}`, "none", "json"),
+        shapesKindIdxUpdate: (params: ShapesKindIdxUpdateParams, body: ShapesKindIdxUpdateRequest): Promise<ShapesKindIdxUpdateResponse> =>
+            call("POST", `shapes/${encodeURIComponent(String(params.kind))}/${encodeURIComponent(String(params.idx))}/update`, "json", "json", body),
+        upload: (body: UploadRequest): Promise<UploadResponse> =>
+            call("POST", "upload", "binary", "json", body),
+    };
+}
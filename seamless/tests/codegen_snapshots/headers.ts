@@ -0,0 +1,87 @@
+// This file was generated by seamless. Do not edit it by hand.
+
+export interface EchoHeaders {
+    "x-request-id": number;
+}
+
+export type EchoRequest = string;
+
+export type EchoResponse = string;
+
+export interface MeHeaders {
+    authorization: string;
+    "x-request-id"?: string;
+}
+
+export type MeResponse = string;
+
+/**
+ * The error thrown when an API route responds with an unsuccessful status code. Client functions
+ * document the type of `body` for the errors that their route is known to respond with.
+ */
+export class ApiRequestError<Body = unknown> extends Error {
+    constructor(public readonly status: number, public readonly body: Body) {
+        super(`Request failed with status ${status}`);
+    }
+}
+
+/** Options to configure the API client with. */
+export interface ClientOptions {
+    /** The URL that route paths are relative to, eg "https://example.com/api". */
+    baseUrl?: string;
+    /** Headers to send along with every request. */
+    headers?: Record<string, string>;
+    /** The `fetch` implementation to use. Defaults to the global `fetch`. */
+    fetch?: typeof fetch;
+}
+
+type RequestKind = "none" | "json" | "binary";
+type ResponseKind = "json" | "binary";
+
+/** Create a client which can make requests to each of the API routes. */
+export function createClient(options: ClientOptions = {}) {
+    const baseUrl = (options.baseUrl ?? "").replace(/\/+$/, "");
+    const doFetch = options.fetch ?? fetch;
+
+    function withQuery(path: string, query: object): string {
+        const params = new URLSearchParams();
+        for (const [key, value] of Object.entries(query)) {
+            for (const v of Array.isArray(value) ? value : [value]) {
+                if (v !== undefined && v !== null) params.append(key, String(v));
+            }
+        }
+        const queryString = params.toString();
+        return queryString ? `${path}?${queryString}` : path;
+    }
+
+    async function call(method: string, path: string, requestKind: RequestKind, responseKind: ResponseKind, body?: unknown, routeHeaders: object = {}): Promise<any> {
+        const headers: Record<string, string> = { ...options.headers };
+        for (const [key, value] of Object.entries(routeHeaders)) {
+            if (value !== undefined && value !== null) headers[key] = String(value);
+        }
+        let requestBody: BodyInit | undefined = undefined;
+        if (requestKind === "json") {
+            headers["content-type"] = "application/json";
+            requestBody = JSON.stringify(body);
+        } else if (requestKind === "binary") {
+            headers["content-type"] = "application/octet-stream";
+            requestBody = body as Blob;
+        }
+
+        const res = await doFetch(`${baseUrl}/${path}`, { method, headers, body: requestBody });
+        if (!res.ok) {
+            const text = await res.text();
+            let errorBody: unknown = text;
+            try { errorBody = JSON.parse(text); } catch {}
+            throw new ApiRequestError(res.status, errorBody);
+        }
+        return responseKind === "binary" ? res.blob() : res.json();
+    }
+
+    return {
+        echo: (headers: EchoHeaders, body: EchoRequest): Promise<EchoResponse> =>
+            call("POST", "echo", "json", "json", body, headers),
+        me: (headers: MeHeaders): Promise<MeResponse> =>
+            call("GET", "me", "none", "json", undefined, headers),
+    };
+}
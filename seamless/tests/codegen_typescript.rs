@@ -0,0 +1,256 @@
+use pretty_assertions::{ assert_eq };
+use seamless::{
+    api::{ Api, ApiBody, ApiError, ProblemDetails },
+    handler::{ body::{ FromJson, FromBinary, Capped, IntoBody }, param::{ Path, Query }, header::{ Header, HeaderKey, Authorization, Bearer }, response::ToJson },
+    codegen::typescript,
+};
+
+// Compare the generated output with the snapshot file given. Set `SEAMLESS_SNAPSHOTS=overwrite`
+// to write the current output to the snapshot file instead.
+fn assert_snapshot(name: &str, actual: String) {
+    let path = format!("{}/tests/codegen_snapshots/{}", env!("CARGO_MANIFEST_DIR"), name);
+    if std::env::var("SEAMLESS_SNAPSHOTS").as_deref() == Ok("overwrite") {
+        std::fs::write(&path, &actual).unwrap();
+    }
+    let expected = std::fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("Could not read snapshot {}: {}", path, e));
+    assert_eq!(actual, expected);
+}
+
+#[derive(ApiError, Debug, thiserror::Error)]
+enum MathsError {
+    #[error("Division by zero")]
+    #[api_error(external, code=400)]
+    DivideByZero
+}
+
+/// Input consisting of two numbers
+#[ApiBody]
+struct BinaryInput {
+    /// Input 'a'
+    a: usize,
+    /// Input 'b'
+    b: usize
+}
+
+/// Output containing the original input and result
+#[ApiBody]
+struct BinaryOutput {
+    a: usize,
+    b: usize,
+    /// The result
+    result: usize
+}
+
+async fn divide(input: BinaryInput) -> Result<ToJson<BinaryOutput>,MathsError> {
+    let a = input.a;
+    let b = input.b;
+    a.checked_div(b)
+        .ok_or(MathsError::DivideByZero)
+        .map(|result| ToJson(BinaryOutput { a, b, result }))
+}
+
+/// The API status
+#[ApiBody]
+struct Status {
+    status: StatusValue,
+    /// Some optional notes
+    notes: Option<Vec<String>>
+}
+
+#[ApiBody]
+enum StatusValue {
+    Ok,
+    NotOk
+}
+
+// The same routes as `examples/basic.rs`:
+#[test]
+fn basic_example() {
+    let mut api = Api::new();
+    api.add("basic/echo")
+        .description("Echoes back a JSON string")
+        .handler(|FromJson(body)| ToJson::<String>(body));
+    api.add("basic/reverse")
+        .description("Reverse an array of numbers")
+        .handler(|body: Capped<FromJson<Vec<usize>>, {8 * 1024}>| {
+            ToJson(body.into_body().into_iter().rev().collect::<Vec<usize>>())
+        });
+    api.add("meta/status")
+        .description("Get the current API status")
+        .handler(|| Some(ToJson(Status { status: StatusValue::Ok, notes: None })));
+    api.add("maths/divide")
+        .description("Divide two numbers by each other")
+        .handler(|FromJson(body)| divide(body));
+
+    assert_snapshot("basic.ts", typescript::generate(&api.info_with_definitions()));
+}
+
+/// Some shape
+#[ApiBody]
+enum Shape {
+    /// A circle
+    Circle {
+        radius: f64
+    },
+    Rect {
+        width: f64,
+        height: f64
+    }
+}
+
+/// Some shapes and things
+#[ApiBody]
+struct Shapes {
+    shapes: Vec<Shape>,
+    lookup: std::collections::HashMap<String, (String, bool)>,
+    /// The "Raw" JSON
+    raw: serde_json::Value
+}
+
+// Check the more unusual shapes:
+#[test]
+fn complex_shapes() {
+    let mut api = Api::new();
+    api.add("shapes.echo")
+        .description("Echo some shapes back\nacross two lines")
+        .handler(|FromJson(body): FromJson<Shapes>| ToJson(body));
+    api.add("upload")
+        .handler(|body: FromBinary| ToJson(body.len()));
+    api.add("shapes/{id}")
+        .handler(|Path(id): Path<usize>| ToJson(id));
+    api.add("files/{*path}")
+        .handler(|Path(path): Path<String>| ToJson(path));
+    api.add("shapes/{kind}/{idx}/update")
+        .handler(|Path(p): Path<(String, usize)>, FromJson(body): FromJson<Shape>| ToJson((p, body)));
+
+    assert_snapshot("complex.ts", typescript::generate(&api.info_with_definitions()));
+}
+
+// Routes in groups are namespaced in the client:
+#[test]
+fn grouped_routes() {
+    let mut api = Api::new();
+    api.add("status")
+        .handler(|| ToJson("ok"));
+    api.group("admin", |admin| {
+        admin.description("Routes for administrators");
+        admin.add("users/{id}")
+            .description("Get a user")
+            .handler(|Path(id): Path<usize>| ToJson(id));
+        admin.group("user-reports", |reports| {
+            reports.add("daily")
+                .handler(|FromJson(day): FromJson<String>| ToJson(day));
+        });
+    });
+
+    assert_snapshot("groups.ts", typescript::generate(&api.info_with_definitions()));
+}
+
+// Group names are only stripped from the start of function names if they're whole words:
+#[test]
+fn group_prefixes_are_whole_words() {
+    let mut api = Api::new();
+    api.group("admin", |admin| {
+        admin.add("things")
+            .operation_id("administerThings")
+            .handler(|| ToJson("ok"));
+        admin.add("users")
+            .handler(|| ToJson("ok"));
+    });
+
+    let ts = typescript::generate(&api.info_with_definitions());
+    assert!(ts.contains("        administerThings: (): Promise<AdministerThingsResponse> =>"), "{}", ts);
+    assert!(ts.contains("        users: (): Promise<AdminUsersResponse> =>"), "{}", ts);
+}
+
+// Functions whose names clash with groups are given a suffix, so that keys aren't duplicated:
+#[test]
+fn route_and_group_names_dont_clash() {
+    let mut api = Api::new();
+    api.add("admin")
+        .handler(|| ToJson("ok"));
+    api.group("admin", |admin| {
+        admin.add("users")
+            .handler(|| ToJson("ok"));
+    });
+
+    let ts = typescript::generate(&api.info_with_definitions());
+    assert!(ts.contains("    admin2: (): Promise<AdminResponse> =>"), "{}", ts);
+    assert_eq!(ts.matches("    admin: {").count(), 1, "{}", ts);
+}
+
+// Summaries and deprecation messages are documented, and operation IDs name functions:
+#[test]
+fn route_metadata() {
+    let mut api = Api::new();
+    api.add("users/list")
+        .summary("List users")
+        .description("List every user")
+        .deprecated("use v2/users/list")
+        .handler(|| ToJson(Vec::<String>::new()));
+    api.add("v2/users/list")
+        .operation_id("list_users")
+        .handler(|FromJson(limit): FromJson<usize>| ToJson(vec![limit]));
+
+    assert_snapshot("metadata.ts", typescript::generate(&api.info_with_definitions()));
+}
+
+// Routes sharing a path are told apart by their method:
+#[test]
+fn routes_sharing_a_path() {
+    let mut api = Api::new();
+    api.add("users")
+        .handler(|| ToJson(Vec::<String>::new()));
+    api.add("users")
+        .handler(|FromJson(name): FromJson<String>| ToJson(name));
+
+    assert_snapshot("shared_path.ts", typescript::generate(&api.info_with_definitions()));
+}
+
+/// What to search for
+#[ApiBody(Deserialize)]
+#[allow(dead_code)]
+struct Search {
+    term: String,
+    tag: Option<Vec<String>>
+}
+
+// Query parameters are handed to client functions as an object:
+#[test]
+fn query_params() {
+    let mut api = Api::new();
+    api.add("users/{id}/posts")
+        .handler(|Path(id): Path<usize>, Query(search): Query<Search>| ToJson(vec![format!("{}: {}", id, search.term)]));
+
+    assert_snapshot("query.ts", typescript::generate(&api.info_with_definitions()));
+}
+
+struct RequestId;
+impl HeaderKey for RequestId {
+    const NAME: &'static str = "x-request-id";
+}
+
+// Headers are handed to client functions as an object:
+#[test]
+fn headers() {
+    let mut api = Api::new();
+    api.add("me")
+        .handler(|Authorization(Bearer(token)), _id: Option<Header<RequestId, String>>| ToJson(token));
+    api.add("echo")
+        .handler(|_id: Header<RequestId, usize>, FromJson(body): FromJson<String>| ToJson(body));
+
+    assert_snapshot("headers.ts", typescript::generate(&api.info_with_definitions()));
+}
+
+// We only know the shape of errors if they're handed back using `ApiError::into_response()`:
+#[test]
+fn route_errors_depend_on_the_error_renderer() {
+    let mut api = Api::new();
+    api.add("maths/divide")
+        .handler(|FromJson(body): FromJson<BinaryInput>| divide(body));
+    assert!(typescript::generate(&api.info_with_definitions()).contains("export type MathsDivideError ="));
+
+    api.render_errors(ProblemDetails);
+    assert!(!typescript::generate(&api.info_with_definitions()).contains("MathsDivideError"));
+}
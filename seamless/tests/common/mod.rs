@@ -0,0 +1,28 @@
+// Helpers shared by the integration tests. Not every test uses all of them:
+#![allow(dead_code)]
+
+use seamless::{
+    api::{ Api, RouteError },
+    handler::request::Bytes,
+};
+use serde_json::Value;
+
+// Hand a request to the API, handing back the response or the status code that it failed with.
+pub async fn respond(api: &Api, req: http::Request<Bytes>) -> Result<http::Response<Vec<u8>>, u16> {
+    match api.handle(req).await {
+        Ok(res) => Ok(res),
+        Err(RouteError::NotFound(_)) => Err(404),
+        Err(RouteError::MethodNotAllowed { .. }) => Err(405),
+        Err(RouteError::Err(e)) => Err(e.code)
+    }
+}
+
+// Hand a request to the API, handing back the JSON response or the status code that it failed with.
+pub async fn send(api: &Api, req: http::Request<Bytes>) -> Result<Value, u16> {
+    respond(api, req).await.map(|res| serde_json::from_slice(res.body()).unwrap())
+}
+
+// Make a GET request with an empty body to the API.
+pub async fn get(api: &Api, path: &str) -> Result<Value, u16> {
+    send(api, http::Request::get(path).body(Bytes::from_vec(vec![])).unwrap()).await
+}
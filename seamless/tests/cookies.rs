@@ -0,0 +1,151 @@
+use pretty_assertions::{ assert_eq };
+use std::time::Duration;
+use seamless::{
+    api::{ Api, ApiError },
+    handler::{ cookie::{ Cookie, Cookies, SameSite }, request::Bytes, response::ToJson },
+};
+use serde_json::{ json, Value };
+
+mod common;
+
+async fn get(api: &Api, path: &str, cookie: Option<&str>) -> Result<(Value, Vec<String>), u16> {
+    let mut req = http::Request::get(path);
+    if let Some(cookie) = cookie {
+        req = req.header("cookie", cookie);
+    }
+    let res = common::respond(api, req.body(Bytes::from_vec(vec![])).unwrap()).await?;
+    let set_cookies = res.headers().get_all("set-cookie").iter().map(|h| h.to_str().unwrap().to_owned()).collect();
+    Ok((serde_json::from_slice(res.body()).unwrap(), set_cookies))
+}
+
+#[tokio::test]
+async fn cookies_can_be_read() {
+    let mut api = Api::new();
+    api.add("read")
+        .handler(|cookies: Cookies| ToJson(json!([cookies.get("a"), cookies.get("b"), cookies.iter().count()])));
+
+    assert_eq!(get(&api, "/read", None).await, Ok((json!([null, null, 0]), vec![])));
+    assert_eq!(
+        get(&api, "/read", Some("a=1; b=\"hello%20there\"; c=")).await,
+        Ok((json!(["1", "hello there", 3]), vec![]))
+    );
+}
+
+#[tokio::test]
+async fn cookies_can_be_set() {
+    let mut api = Api::new();
+    api.add("set")
+        .handler(|cookies: Cookies| {
+            cookies.add(Cookie::new("plain", "a value; with semicolons"));
+            cookies.add(
+                Cookie::new("session", "abc")
+                    .path("/")
+                    .domain("example.com")
+                    .max_age(Duration::from_secs(3600))
+                    .secure(true)
+                    .http_only(true)
+                    .same_site(SameSite::Lax)
+            );
+            cookies.remove(Cookie::new("old", "").path("/"));
+            ToJson(())
+        });
+    api.add("fails")
+        .handler(|cookies: Cookies| {
+            cookies.add(Cookie::new("plain", "1"));
+            Err::<ToJson<()>, _>(ApiError::server_error("oops"))
+        });
+
+    assert_eq!(get(&api, "/set", None).await, Ok((json!(null), vec![
+        "plain=a%20value%3B%20with%20semicolons".to_owned(),
+        "session=abc; Path=/; Domain=example.com; Max-Age=3600; Secure; HttpOnly; SameSite=Lax".to_owned(),
+        "old=; Path=/; Max-Age=0".to_owned(),
+    ])));
+
+    // Cookies set by handlers which fail aren't sent:
+    assert_eq!(get(&api, "/fails", None).await, Err(500));
+}
+
+#[cfg(feature = "secure-cookies")]
+mod secure {
+    use pretty_assertions::{ assert_eq };
+    use seamless::{
+        api::{ Api, ApiError },
+        handler::{ cookie::{ Cookie, CookieKey, Cookies }, response::ToJson },
+    };
+    use serde_json::json;
+    use super::get;
+
+    fn secure_api(key: CookieKey) -> Api {
+        let mut api = Api::new();
+        api.cookie_key(key);
+        api.add("set")
+            .handler(|cookies: Cookies| -> Result<ToJson<()>, ApiError> {
+                cookies.add_signed(Cookie::new("signed", "user-1"))?;
+                cookies.add_private(Cookie::new("private", "secret"))?;
+                Ok(ToJson(()))
+            });
+        api.add("get")
+            .handler(|cookies: Cookies| ToJson(json!([cookies.get_signed("signed"), cookies.get_private("private")])));
+        api
+    }
+
+    fn cookie_header(set_cookies: &[String]) -> String {
+        set_cookies.iter().map(|c| c.split(';').next().unwrap()).collect::<Vec<_>>().join("; ")
+    }
+
+    #[tokio::test]
+    async fn signed_and_private_cookies_round_trip() {
+        let api = secure_api(CookieKey::from_bytes(&[7; 64]).unwrap());
+        let (_, set_cookies) = get(&api, "/set", None).await.unwrap();
+        assert_eq!(set_cookies.len(), 2);
+        assert!(set_cookies[0].starts_with("signed=") && set_cookies[0].ends_with("user-1"));
+        assert!(!set_cookies[1].contains("secret"));
+
+        let cookies = cookie_header(&set_cookies);
+        assert_eq!(get(&api, "/get", Some(&cookies)).await.unwrap().0, json!(["user-1", "secret"]));
+    }
+
+    #[tokio::test]
+    async fn tampered_cookies_are_rejected() {
+        let api = secure_api(CookieKey::generate());
+        let (_, set_cookies) = get(&api, "/set", None).await.unwrap();
+        let cookies = cookie_header(&set_cookies);
+
+        // Changing the value of the signed cookie invalidates it:
+        let tampered = cookies.replace("user-1", "user-2");
+        assert_eq!(get(&api, "/get", Some(&tampered)).await.unwrap().0, json!([null, "secret"]));
+
+        // Moving values between cookies invalidates them:
+        let (signed, private) = cookies.split_once("; ").unwrap();
+        let swapped = format!("{}; {}", signed.replace("signed=", "other="), private.replace("private=", "signed="));
+        assert_eq!(get(&api, "/get", Some(&swapped)).await.unwrap().0, json!([null, null]));
+
+        // Cookies set using a different key can't be read:
+        let other_api = secure_api(CookieKey::generate());
+        assert_eq!(get(&other_api, "/get", Some(&cookies)).await.unwrap().0, json!([null, null]));
+    }
+
+    #[tokio::test]
+    async fn signatures_dont_verify_under_shifted_names() {
+        let mut api = secure_api(CookieKey::generate());
+        api.add("shifted")
+            .handler(|cookies: Cookies| ToJson(json!([cookies.get_signed("signe")])));
+        let (_, set_cookies) = get(&api, "/set", None).await.unwrap();
+
+        // "signed" = "user-1" and "signe" = "duser-1" would be signed the same way if
+        // names and values were simply joined together:
+        let shifted = set_cookies[0].split(';').next().unwrap()
+            .replacen("signed=", "signe=", 1)
+            .replace("user-1", "duser-1");
+        assert_eq!(get(&api, "/shifted", Some(&shifted)).await.unwrap().0, json!([null]));
+    }
+
+    #[tokio::test]
+    async fn secure_cookies_need_a_key() {
+        let mut api = Api::new();
+        api.add("set")
+            .handler(|cookies: Cookies| cookies.add_signed(Cookie::new("a", "b")).map(|_| ToJson(())));
+        assert_eq!(get(&api, "/set", None).await, Err(500));
+        assert!(CookieKey::from_bytes(&[0; 63]).is_none());
+    }
+}
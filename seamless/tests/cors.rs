@@ -0,0 +1,139 @@
+use pretty_assertions::{ assert_eq };
+use seamless::{
+    api::{ Api, CorsConfig, RouteError },
+    handler::{ body::FromJson, param::Path, request::Bytes, response::ToJson },
+};
+use std::time::Duration;
+
+fn cors_api() -> Api {
+    let mut api = Api::new_with_base_path("api");
+    api.cors(
+        CorsConfig::new()
+            .allow_origin("https://example.com")
+            .allow_headers(["Content-Type"])
+            .expose_headers(["x-total"])
+            .max_age(Duration::from_secs(600))
+    );
+    api.add("echo")
+        .handler(|FromJson(body): FromJson<String>| ToJson(body));
+    api.add("users/{id}")
+        .handler(|Path(id): Path<usize>| ToJson(id));
+    api.add("public")
+        .cors(CorsConfig::new().allow_any_origin())
+        .handler(|| ToJson("public"));
+    api
+}
+
+fn req(method: &str, path: &str, headers: &[(&str, &str)]) -> http::Request<Bytes> {
+    let mut req = http::Request::builder()
+        .method(method)
+        .uri(path);
+    for (name, value) in headers {
+        req = req.header(*name, *value);
+    }
+    req.body(Bytes::from_vec(b"\"hi\"".to_vec())).unwrap()
+}
+
+fn header<'a>(res: &'a http::Response<Vec<u8>>, name: &str) -> Option<&'a str> {
+    res.headers().get(name).map(|v| v.to_str().unwrap())
+}
+
+#[tokio::test]
+async fn preflight_requests_are_answered() {
+    let api = cors_api();
+    let res = api.handle(req("OPTIONS", "/api/echo", &[
+        ("origin", "https://example.com"),
+        ("access-control-request-method", "POST"),
+        ("access-control-request-headers", "content-type"),
+    ])).await.unwrap();
+
+    assert_eq!(res.status(), 204);
+    assert_eq!(header(&res, "access-control-allow-origin"), Some("https://example.com"));
+    assert_eq!(header(&res, "access-control-allow-methods"), Some("POST"));
+    assert_eq!(header(&res, "access-control-allow-headers"), Some("content-type"));
+    assert_eq!(header(&res, "access-control-max-age"), Some("600"));
+    assert_eq!(header(&res, "vary"), Some("Origin"));
+
+    // The method of the route is used to answer preflights:
+    let res = api.handle(req("OPTIONS", "/api/users/1", &[
+        ("origin", "https://example.com"),
+        ("access-control-request-method", "GET"),
+    ])).await.unwrap();
+    assert_eq!(header(&res, "access-control-allow-methods"), Some("GET, HEAD"));
+}
+
+#[tokio::test]
+async fn disallowed_preflight_requests_get_no_cors_headers() {
+    let api = cors_api();
+    let disallowed = [
+        // Unknown origin:
+        [("origin", "https://other.com"), ("access-control-request-method", "POST"), ("access-control-request-headers", "")],
+        // No route with this method:
+        [("origin", "https://example.com"), ("access-control-request-method", "PUT"), ("access-control-request-headers", "")],
+        // Header not allowed:
+        [("origin", "https://example.com"), ("access-control-request-method", "POST"), ("access-control-request-headers", "x-custom")],
+    ];
+    for headers in disallowed {
+        let res = api.handle(req("OPTIONS", "/api/echo", &headers)).await.unwrap();
+        assert_eq!(header(&res, "access-control-allow-origin"), None);
+        // We fall back to the standard response to OPTIONS requests:
+        assert_eq!(header(&res, "allow"), Some("OPTIONS, POST"));
+    }
+}
+
+#[tokio::test]
+async fn headers_are_added_to_responses() {
+    let api = cors_api();
+    let res = api.handle(req("POST", "/api/echo", &[
+        ("origin", "https://example.com"),
+        ("content-type", "application/json"),
+    ])).await.unwrap();
+    assert_eq!(res.body(), b"\"hi\"");
+    assert_eq!(header(&res, "access-control-allow-origin"), Some("https://example.com"));
+    assert_eq!(header(&res, "access-control-expose-headers"), Some("x-total"));
+
+    // Nothing is added for other origins, or when no origin is given:
+    let res = api.handle(req("POST", "/api/echo", &[
+        ("origin", "https://other.com"),
+        ("content-type", "application/json"),
+    ])).await.unwrap();
+    assert_eq!(header(&res, "access-control-allow-origin"), None);
+    let res = api.handle(req("POST", "/api/echo", &[
+        ("content-type", "application/json"),
+    ])).await.unwrap();
+    assert_eq!(header(&res, "access-control-allow-origin"), None);
+}
+
+#[tokio::test]
+async fn routes_can_override_cors_config() {
+    let api = cors_api();
+    let res = api.handle(req("GET", "/api/public", &[("origin", "https://other.com")])).await.unwrap();
+    assert_eq!(header(&res, "access-control-allow-origin"), Some("*"));
+    assert_eq!(header(&res, "vary"), None);
+}
+
+#[tokio::test]
+async fn credentials_echo_the_origin() {
+    let mut api = Api::new();
+    api.add("me")
+        .cors(CorsConfig::new().allow_any_origin().allow_credentials(true))
+        .handler(|| ToJson("me"));
+    let res = api.handle(req("GET", "/me", &[("origin", "https://other.com")])).await.unwrap();
+    assert_eq!(header(&res, "access-control-allow-origin"), Some("https://other.com"));
+    assert_eq!(header(&res, "access-control-allow-credentials"), Some("true"));
+}
+
+#[tokio::test]
+async fn no_cors_by_default() {
+    let mut api = Api::new();
+    api.add("echo")
+        .handler(|FromJson(body): FromJson<String>| ToJson(body));
+    let res = api.handle(req("OPTIONS", "/echo", &[
+        ("origin", "https://example.com"),
+        ("access-control-request-method", "POST"),
+    ])).await.unwrap();
+    assert_eq!(header(&res, "access-control-allow-origin"), None);
+
+    let res = api.handle(req("GET", "/echo", &[("origin", "https://example.com")])).await;
+    assert!(matches!(res, Err(RouteError::MethodNotAllowed { .. })));
+}
@@ -0,0 +1,153 @@
+use pretty_assertions::{ assert_eq };
+use seamless::{
+    api::{ Api, ApiBodyType, ApiError, CorsConfig, JsonErrors, ProblemDetails, RouteError },
+    handler::{ request::Bytes, response::ToJson },
+};
+use serde_json::{ json, Value };
+
+fn error(code: u16, value: Option<Value>) -> ApiError {
+    ApiError {
+        code,
+        internal_message: "something secret".to_owned(),
+        external_message: "Something went wrong".to_owned(),
+        value,
+        problem_type: None,
+        error_code: None
+    }
+}
+
+fn body(res: &http::Response<Vec<u8>>) -> Value {
+    serde_json::from_slice(res.body()).unwrap()
+}
+
+#[test]
+fn errors_are_converted_into_responses() {
+    let res = ApiError { error_code: Some("users.conflict".to_owned()), ..error(409, Some(json!({ "id": 3 }))) }.into_response();
+    assert_eq!(res.status(), 409);
+    assert_eq!(res.headers()["content-type"], "application/json");
+    assert_eq!(body(&res), json!({
+        "code": 409,
+        "error_code": "users.conflict",
+        "external_message": "Something went wrong",
+        "value": { "id": 3 }
+    }));
+    assert!(!String::from_utf8(res.into_body()).unwrap().contains("secret"));
+
+    let res = error(404, None).into_response();
+    assert_eq!(body(&res), json!({ "code": 404, "error_code": null, "external_message": "Something went wrong", "value": null }));
+
+    // Codes which aren't valid HTTP status codes become server errors:
+    let res = error(42, None).into_response();
+    assert_eq!(res.status(), 500);
+    assert_eq!(body(&res)["code"], json!(500));
+}
+
+#[test]
+fn response_body_info_describes_responses() {
+    let info = ApiError::response_body_info();
+    let keys = match info.ty {
+        ApiBodyType::Object { keys } => keys,
+        other => panic!("expected an object, got {:?}", other)
+    };
+    let body = body(&error(400, Some(json!([1, 2]))).into_response());
+    let mut body_keys: Vec<_> = body.as_object().unwrap().keys().cloned().collect();
+    let mut info_keys: Vec<_> = keys.keys().cloned().collect();
+    body_keys.sort();
+    info_keys.sort();
+    assert_eq!(body_keys, info_keys);
+    assert_eq!(keys["code"].ty, ApiBodyType::Number);
+    assert_eq!(keys["external_message"].ty, ApiBodyType::String);
+}
+
+fn failing_api() -> Api {
+    let mut api = Api::new();
+    api.cors(CorsConfig::new().allow_origin("https://example.com"));
+    api.add("fails")
+        .handler(|| Err::<ToJson<()>, _>(error(418, None)));
+    api.add("works")
+        .handler(|| ToJson("ok"));
+    api
+}
+
+fn req(path: &str) -> http::Request<Bytes> {
+    http::Request::get(path)
+        .header("origin", "https://example.com")
+        .body(Bytes::from_vec(vec![]))
+        .unwrap()
+}
+
+#[tokio::test]
+async fn errors_are_handed_back_by_default() {
+    let api = failing_api();
+    match api.handle(req("/fails")).await {
+        Err(RouteError::Err(e)) => assert_eq!(e, error(418, None)),
+        other => panic!("expected an error, got {:?}", other.map(|res| res.status()))
+    }
+}
+
+#[tokio::test]
+async fn errors_can_be_rendered_by_the_api() {
+    let mut api = failing_api();
+    api.render_errors(JsonErrors);
+
+    let res = api.handle(req("/fails")).await.unwrap();
+    assert_eq!(res.status(), 418);
+    assert_eq!(res.headers()["access-control-allow-origin"], "https://example.com");
+    assert_eq!(body(&res), json!({ "code": 418, "error_code": null, "external_message": "Something went wrong", "value": null }));
+
+    // Other routes and unknown paths are unaffected:
+    assert_eq!(api.handle(req("/works")).await.unwrap().status(), 200);
+    assert!(matches!(api.handle(req("/unknown")).await, Err(RouteError::NotFound(_))));
+}
+
+#[tokio::test]
+async fn errors_can_be_rendered_as_problem_details() {
+    let mut api = Api::new_with_base_path("api");
+    api.render_errors(ProblemDetails);
+    api.add("object")
+        .handler(|| Err::<ToJson<()>, _>(ApiError {
+            problem_type: Some("https://example.com/probs/out-of-credit".to_owned()),
+            error_code: Some("billing.out_of_credit".to_owned()),
+            ..error(403, Some(json!({ "balance": 30, "status": 200, "error_code": "overridden" })))
+        }));
+    api.add("other")
+        .handler(|| Err::<ToJson<()>, _>(error(400, Some(json!([1, 2])))));
+
+    let res = api.handle(http::Request::get("/api/object?a=1").body(Bytes::from_vec(vec![])).unwrap()).await.unwrap();
+    assert_eq!(res.status(), 403);
+    assert_eq!(res.headers()["content-type"], "application/problem+json");
+    // Extension members can't override the standard ones or the error code:
+    assert_eq!(body(&res), json!({
+        "type": "https://example.com/probs/out-of-credit",
+        "title": "Forbidden",
+        "status": 403,
+        "detail": "Something went wrong",
+        "instance": "/api/object",
+        "error_code": "billing.out_of_credit",
+        "balance": 30
+    }));
+
+    let res = api.handle(http::Request::get("/api/other").body(Bytes::from_vec(vec![])).unwrap()).await.unwrap();
+    assert_eq!(body(&res), json!({
+        "type": "about:blank",
+        "title": "Bad Request",
+        "status": 400,
+        "detail": "Something went wrong",
+        "instance": "/api/other",
+        "value": [1, 2]
+    }));
+}
+
+#[test]
+fn error_responses_are_described() {
+    let mut api = failing_api();
+    let info = api.info_with_definitions();
+    assert_eq!(info.error_response.content_type, "application/json");
+    assert_eq!(info.error_response.body, ApiError::response_body_info());
+
+    api.render_errors(ProblemDetails);
+    let openapi = api.openapi();
+    let error_content = &openapi["paths"]["/fails"]["get"]["responses"]["default"]["content"];
+    assert!(error_content.get("application/problem+json").is_some(), "{}", error_content);
+    assert_eq!(openapi["components"]["schemas"]["ApiError"]["required"], json!(["detail", "instance", "status", "title", "type"]));
+}
@@ -0,0 +1,145 @@
+use pretty_assertions::{ assert_eq };
+use std::sync::{ Arc, Mutex };
+use seamless::{
+    api::{ Api, ApiError, CorsConfig, RouteGroupInfo, middleware_fn },
+    handler::{ param::Path, request::Bytes, response::ToJson },
+};
+use serde_json::{ json };
+
+mod common;
+use common::{ get };
+
+fn s(s: &str) -> String {
+    s.to_owned()
+}
+
+fn admin_api() -> Api {
+    let mut api = Api::new_with_base_path("api");
+    api.add("status")
+        .handler(|| ToJson("ok"));
+    api.group("/admin/", |admin| {
+        admin.description("Admin routes");
+        admin.add("users/{id}")
+            .handler(|Path(id): Path<usize>| ToJson(json!({ "user": id })));
+        admin.group("orgs/{org}", |orgs| {
+            orgs.add("members")
+                .description("Org members")
+                .handler(|Path(org): Path<String>| ToJson(json!({ "members": org })));
+        });
+    });
+    api
+}
+
+#[tokio::test]
+async fn group_prefixes_are_applied() {
+    let api = admin_api();
+    assert_eq!(get(&api, "/api/status").await, Ok(json!("ok")));
+    assert_eq!(get(&api, "/api/admin/users/1").await, Ok(json!({ "user": 1 })));
+    assert_eq!(get(&api, "/api/admin/orgs/acme/members").await, Ok(json!({ "members": "acme" })));
+    assert_eq!(get(&api, "/api/users/1").await, Err(404));
+}
+
+#[test]
+fn groups_are_in_route_info() {
+    let info = admin_api().info();
+    let groups: Vec<_> = info.iter()
+        .map(|r| (r.name.as_str(), r.description.as_str(), r.groups.clone()))
+        .collect();
+
+    let admin = RouteGroupInfo { name: s("admin"), description: s("Admin routes") };
+    let orgs = RouteGroupInfo { name: s("orgs/{org}"), description: s("") };
+    assert_eq!(groups, vec![
+        ("admin/orgs/{org}/members", "Org members", vec![admin.clone(), orgs]),
+        ("admin/users/{id}", "", vec![admin]),
+        ("status", "", vec![]),
+    ]);
+}
+
+#[tokio::test]
+async fn group_middleware_applies_to_every_route_in_it() {
+    let log = Arc::new(Mutex::new(vec![]));
+    let record = |name: &'static str| {
+        let log = log.clone();
+        middleware_fn(move |req, next| {
+            log.lock().unwrap().push(name);
+            next.run(req)
+        })
+    };
+
+    let mut api = Api::new();
+    api.wrap(record("api"));
+    api.add("open")
+        .handler(|| ToJson(0));
+    api.group("outer", |outer| {
+        outer.add("a")
+            .wrap(record("route"))
+            .handler(|| ToJson(1));
+        outer.group("inner", |inner| {
+            inner.wrap(record("inner"));
+            inner.add("b").handler(|| ToJson(2));
+        });
+        // Applies to the routes added above, too:
+        outer.wrap(record("outer"));
+    });
+
+    assert_eq!(get(&api, "/open").await, Ok(json!(0)));
+    assert_eq!(*log.lock().unwrap(), vec!["api"]);
+
+    log.lock().unwrap().clear();
+    assert_eq!(get(&api, "/outer/a").await, Ok(json!(1)));
+    assert_eq!(*log.lock().unwrap(), vec!["api", "outer", "route"]);
+
+    log.lock().unwrap().clear();
+    assert_eq!(get(&api, "/outer/inner/b").await, Ok(json!(2)));
+    assert_eq!(*log.lock().unwrap(), vec!["api", "outer", "inner"]);
+}
+
+#[tokio::test]
+async fn group_middleware_can_guard_routes() {
+    let mut api = Api::new();
+    api.group("admin", |admin| {
+        admin.wrap(middleware_fn(|req, next| Box::pin(async move {
+            if !req.headers().contains_key("x-admin") {
+                return Err(ApiError::not_authorized("Admins only"))
+            }
+            next.run(req).await
+        })));
+        admin.add("secret").handler(|| ToJson("secret"));
+    });
+
+    assert_eq!(get(&api, "/admin/secret").await, Err(403));
+    let req = http::Request::get("/admin/secret")
+        .header("x-admin", "yes")
+        .body(Bytes::from_vec(vec![]))
+        .unwrap();
+    assert_eq!(api.handle(req).await.unwrap().into_body(), br#""secret""#.to_vec());
+}
+
+#[tokio::test]
+async fn group_cors_can_be_overridden() {
+    let mut api = Api::new();
+    api.cors(CorsConfig::new().allow_origin("https://api.example"));
+    api.group("admin", |admin| {
+        admin.cors(CorsConfig::new().allow_origin("https://admin.example"));
+        admin.add("a").handler(|| ToJson(1));
+        admin.add("b")
+            .cors(CorsConfig::new().allow_origin("https://b.example"))
+            .handler(|| ToJson(2));
+    });
+    api.add("c").handler(|| ToJson(3));
+
+    let allowed_origin = |path: &str| {
+        let req = http::Request::get(path)
+            .header("origin", "https://admin.example")
+            .body(Bytes::from_vec(vec![]))
+            .unwrap();
+        let api = &api;
+        async move {
+            let res = api.handle(req).await.unwrap();
+            res.headers().get("access-control-allow-origin").map(|h| h.to_str().unwrap().to_owned())
+        }
+    };
+    assert_eq!(allowed_origin("/admin/a").await, Some(s("https://admin.example")));
+    assert_eq!(allowed_origin("/admin/b").await, None);
+    assert_eq!(allowed_origin("/c").await, None);
+}
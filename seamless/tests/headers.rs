@@ -0,0 +1,109 @@
+use pretty_assertions::{ assert_eq };
+use seamless::{
+    api::{ Api, ApiBodyInfo, ApiBodyType, ApiError, HeaderParamInfo, RouteError },
+    handler::{
+        header::{ Header, HeaderKey, Headers, ContentType, UserAgent, AcceptLanguage, Authorization, Bearer },
+        request::Bytes,
+        response::ToJson
+    },
+};
+use serde_json::{ json, Value };
+
+struct PageSize;
+impl HeaderKey for PageSize {
+    const NAME: &'static str = "X-Page-Size";
+}
+
+fn headers_api() -> Api {
+    let mut api = Api::new();
+    api.add("page")
+        .handler(|size: Header<PageSize, usize>| ToJson(size.into_inner()));
+    api.add("me")
+        .handler(|Authorization(Bearer(token)), UserAgent(agent)| ToJson(json!([token, agent])));
+    api.add("languages")
+        .handler(|AcceptLanguage(languages)| ToJson(languages));
+    api.add("content-type")
+        .handler(|content_type: ContentType| ToJson(json!([content_type.media_type(), content_type.param("charset")])));
+    api.add("optional")
+        .handler(|size: Option<Header<PageSize, usize>>, headers: Headers| {
+            ToJson(json!([size.map(|s| s.into_inner()), headers.len()]))
+        });
+    api
+}
+
+async fn get(api: &Api, headers: &[(&str, &str)], path: &str) -> Result<Value, ApiError> {
+    let mut req = http::Request::get(path);
+    for (name, value) in headers {
+        req = req.header(*name, *value);
+    }
+    match api.handle(req.body(Bytes::from_vec(vec![])).unwrap()).await {
+        Ok(res) => Ok(serde_json::from_slice(&res.into_body()).unwrap()),
+        Err(RouteError::Err(e)) => Err(e),
+        Err(_) => panic!("route not found: {}", path)
+    }
+}
+
+fn s(s: &str) -> String {
+    s.to_owned()
+}
+
+#[tokio::test]
+async fn headers_are_extracted() {
+    let api = headers_api();
+    assert_eq!(get(&api, &[("x-page-size", "10")], "/page").await, Ok(json!(10)));
+    assert_eq!(
+        get(&api, &[("authorization", "bearer abc123"), ("user-agent", "tests")], "/me").await,
+        Ok(json!(["abc123", "tests"]))
+    );
+    assert_eq!(
+        get(&api, &[("accept-language", "fr;q=0.5, en-GB, de;q=0, en;q=0.8")], "/languages").await,
+        Ok(json!(["en-GB", "en", "fr"]))
+    );
+    assert_eq!(
+        get(&api, &[("content-type", "Text/HTML; charset=\"utf-8\"")], "/content-type").await,
+        Ok(json!(["text/html", "utf-8"]))
+    );
+    assert_eq!(get(&api, &[("x-page-size", "3"), ("x-other", "a")], "/optional").await, Ok(json!([3, 2])));
+    assert_eq!(get(&api, &[], "/optional").await, Ok(json!([null, 0])));
+}
+
+#[tokio::test]
+async fn missing_and_invalid_headers_are_rejected() {
+    let api = headers_api();
+    // The headers sent, the path they're sent to and the header that should be rejected:
+    type Case = (&'static [(&'static str, &'static str)], &'static str, &'static str);
+    let cases: &[Case] = &[
+        (&[], "/page", "x-page-size"),
+        (&[("x-page-size", "lots")], "/page", "x-page-size"),
+        (&[("user-agent", "tests")], "/me", "authorization"),
+        (&[("authorization", "Basic abc"), ("user-agent", "tests")], "/me", "authorization"),
+        (&[("authorization", "Bearer"), ("user-agent", "tests")], "/me", "authorization"),
+        (&[("authorization", "Bearer abc")], "/me", "user-agent"),
+        (&[("accept-language", "en;q=2")], "/languages", "accept-language"),
+        (&[], "/content-type", "content-type"),
+    ];
+    for (headers, path, header) in cases {
+        let err = get(&api, headers, path).await.unwrap_err();
+        assert_eq!(err.code, 400, "{:?} {}", headers, path);
+        assert_eq!(err.value, Some(json!({ "header": header })), "{:?} {}", headers, path);
+        assert!(err.external_message.contains(&format!("'{}'", header)), "{}", err.external_message);
+    }
+}
+
+#[test]
+fn headers_are_in_route_info() {
+    let api = headers_api();
+    let headers = |name: &str| api.info().into_iter().find(|r| r.name == name).unwrap().headers;
+    let header = |name: &str, required: bool, ty: ApiBodyType| HeaderParamInfo {
+        name: s(name),
+        required,
+        ty: ApiBodyInfo { description: s(""), ty }
+    };
+
+    assert_eq!(headers("page"), vec![header("x-page-size", true, ApiBodyType::Number)]);
+    assert_eq!(headers("me"), vec![
+        header("authorization", true, ApiBodyType::String),
+        header("user-agent", true, ApiBodyType::String)
+    ]);
+    assert_eq!(headers("optional"), vec![header("x-page-size", false, ApiBodyType::Number)]);
+}
@@ -0,0 +1,138 @@
+use pretty_assertions::{ assert_eq };
+use std::sync::{ Arc, Mutex };
+use seamless::{
+    api::{ Api, ApiError, RouteError, Middleware, MiddlewareFuture, Next, middleware_fn },
+    handler::{ HandlerParam, param::Path, request::{ AsyncReadBody, Bytes }, response::ToJson },
+};
+use serde_json::{ json };
+
+mod common;
+use common::{ get };
+
+// Records its name before and after running the rest of the chain.
+struct Record {
+    name: &'static str,
+    log: Arc<Mutex<Vec<String>>>
+}
+
+impl Middleware for Record {
+    fn handle<'a>(&'a self, req: http::Request<&'a mut dyn AsyncReadBody>, next: Next<'a>) -> MiddlewareFuture<'a> {
+        Box::pin(async move {
+            self.log.lock().unwrap().push(format!("before {}", self.name));
+            let res = next.run(req).await;
+            self.log.lock().unwrap().push(format!("after {}", self.name));
+            res
+        })
+    }
+}
+
+// Middleware can put this into requests for handlers to ask for.
+#[derive(Clone)]
+struct SeenBy(&'static str);
+
+#[seamless::async_trait]
+impl HandlerParam for SeenBy {
+    type Error = ApiError;
+    async fn handler_param(req: &http::Request<()>) -> Result<Self,Self::Error> {
+        req.extensions().get::<SeenBy>().cloned().ok_or_else(|| ApiError::server_error("Not seen"))
+    }
+}
+
+fn req(path: &str) -> http::Request<Bytes> {
+    http::Request::get(path).body(Bytes::from_vec(vec![])).unwrap()
+}
+
+#[tokio::test]
+async fn middleware_runs_in_order() {
+    let log = Arc::new(Mutex::new(vec![]));
+    let record = |name| Record { name, log: log.clone() };
+
+    let mut api = Api::new();
+    api.wrap(record("api 1"));
+    api.add("wrapped")
+        .wrap(record("route 1"))
+        .wrap(record("route 2"))
+        .handler(|| ToJson(1));
+    api.add("plain")
+        .handler(|| ToJson(2));
+    // Middleware added to the API applies to routes added before it, too:
+    api.wrap(record("api 2"));
+
+    assert_eq!(get(&api, "/wrapped").await, Ok(json!(1)));
+    assert_eq!(*log.lock().unwrap(), vec![
+        "before api 1", "before api 2", "before route 1", "before route 2",
+        "after route 2", "after route 1", "after api 2", "after api 1"
+    ]);
+
+    log.lock().unwrap().clear();
+    assert_eq!(get(&api, "/plain").await, Ok(json!(2)));
+    assert_eq!(*log.lock().unwrap(), vec![
+        "before api 1", "before api 2", "after api 2", "after api 1"
+    ]);
+
+    // Middleware doesn't run if no route matches:
+    log.lock().unwrap().clear();
+    assert_eq!(get(&api, "/nope").await, Err(404));
+    assert!(log.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn middleware_can_short_circuit() {
+    let mut api = Api::new();
+    api.add("secret/{id}")
+        .wrap(middleware_fn(|req, next| Box::pin(async move {
+            if req.headers().get("authorization").map(|h| h == "let me in") != Some(true) {
+                return Err(ApiError::not_authorized("Nope"))
+            }
+            next.run(req).await
+        })))
+        .handler(|Path(id): Path<usize>| ToJson(id));
+
+    assert_eq!(get(&api, "/secret/1").await, Err(403));
+
+    let req = http::Request::get("/secret/2")
+        .header("authorization", "let me in")
+        .body(Bytes::from_vec(vec![]))
+        .unwrap();
+    let res = api.handle(req).await.unwrap();
+    assert_eq!(res.into_body(), b"2".to_vec());
+}
+
+#[tokio::test]
+async fn middleware_can_modify_requests_and_responses() {
+    let mut api = Api::new();
+    api.wrap(middleware_fn(|mut req, next| Box::pin(async move {
+        req.extensions_mut().insert(SeenBy("middleware"));
+        let mut res = next.run(req).await?;
+        res.headers_mut().insert("x-powered-by", "seamless".parse().unwrap());
+        Ok(res)
+    })));
+    api.add("seen")
+        .handler(|SeenBy(by)| ToJson(by));
+
+    let res = api.handle(req("/seen")).await.unwrap();
+    assert_eq!(res.headers()["x-powered-by"], "seamless");
+    assert_eq!(res.into_body(), br#""middleware""#.to_vec());
+}
+
+#[tokio::test]
+async fn middleware_sees_handler_errors() {
+    let mut api = Api::new();
+    api.wrap(middleware_fn(|req, next| Box::pin(async move {
+        next.run(req).await.map_err(|mut e| {
+            e.external_message = format!("wrapped: {}", e.external_message);
+            e
+        })
+    })));
+    api.add("fails")
+        .handler(|| Err::<ToJson<()>, _>(ApiError::server_error("oops")));
+
+    match api.handle(req("/fails")).await {
+        Err(RouteError::Err(e)) => {
+            assert_eq!(e.code, 500);
+            assert_eq!(e.internal_message, "oops");
+            assert_eq!(e.external_message, "wrapped: Internal server error");
+        },
+        other => panic!("expected an error, got {:?}", other.map(|r| r.into_body()))
+    }
+}
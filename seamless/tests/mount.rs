@@ -0,0 +1,147 @@
+use pretty_assertions::{ assert_eq };
+use seamless::{
+    api::{ Api, ApiBody, ApiBodyType, RouteConflict, middleware_fn },
+    handler::{ body::FromJson, param::Path, request::Bytes, response::ToJson },
+};
+use serde_json::{ json };
+
+mod common;
+use common::{ get };
+
+mod users {
+    use super::*;
+
+    /// A user
+    #[ApiBody]
+    pub struct Item {
+        pub name: String
+    }
+
+    pub fn api() -> Api {
+        // The base path is ignored when mounting:
+        let mut api = Api::new_with_base_path("ignored");
+        api.add("list")
+            .handler(|| ToJson(vec![Item { name: "Alice".to_owned() }]));
+        api.add("{id}")
+            .handler(|Path(id): Path<usize>| ToJson(id));
+        api
+    }
+}
+
+mod orders {
+    use super::*;
+
+    /// An order
+    #[ApiBody]
+    pub struct Item {
+        pub id: usize,
+        pub user: users::Item
+    }
+
+    pub fn api() -> Api {
+        let mut api = Api::new();
+        api.add("list")
+            .handler(|| ToJson(Vec::<Item>::new()));
+        api
+    }
+}
+
+fn names(api: &Api) -> Vec<String> {
+    api.info().into_iter().map(|r| r.name).collect()
+}
+
+#[tokio::test]
+async fn mounted_routes_are_handled() {
+    let mut api = Api::new_with_base_path("api");
+    api.add("status").handler(|| ToJson("ok"));
+    api.mount("/users/", users::api()).unwrap();
+    api.mount("orders", orders::api()).unwrap();
+
+    assert_eq!(names(&api), vec!["orders/list", "status", "users/list", "users/{id}"]);
+    assert_eq!(get(&api, "/api/users/list").await, Ok(json!([{ "name": "Alice" }])));
+    assert_eq!(get(&api, "/api/users/3").await, Ok(json!(3)));
+    assert_eq!(get(&api, "/api/orders/list").await, Ok(json!([])));
+    assert_eq!(get(&api, "/api/list").await, Err(404));
+}
+
+#[test]
+fn named_types_are_merged() {
+    let mut api = Api::new();
+    api.mount("users", users::api()).unwrap();
+    api.mount("orders", orders::api()).unwrap();
+
+    let info = api.info_with_definitions();
+    let definitions: Vec<_> = info.definitions.iter()
+        .map(|(name, d)| (name.as_str(), d.description.as_str()))
+        .collect();
+    // Both are called "Item", so one is renamed, and `users::Item` is only described once:
+    assert_eq!(definitions, vec![("Item", "A user"), ("Item2", "An order")]);
+
+    let ref_to = |name: &str| ApiBodyType::Ref { name: name.to_owned() };
+    let orders_list = info.routes.iter().find(|r| r.name == "orders/list").unwrap();
+    match &orders_list.response_type.ty {
+        ApiBodyType::ArrayOf { value } => assert_eq!(value.ty, ref_to("Item2")),
+        ty => panic!("expected an array, got {:?}", ty)
+    }
+    match &info.definitions["Item2"].ty {
+        ApiBodyType::Object { keys } => assert_eq!(keys["user"].ty, ref_to("Item")),
+        ty => panic!("expected an object, got {:?}", ty)
+    }
+}
+
+#[test]
+fn conflicts_are_reported() {
+    let mut api = Api::new();
+    api.add("users/{name}").handler(|Path(name): Path<String>| ToJson(name));
+    api.add("users/list").handler(|FromJson(body): FromJson<String>| ToJson(body));
+
+    let err: RouteConflict = api.mount("users", users::api()).unwrap_err();
+    assert_eq!(err.method, http::Method::GET);
+    assert_eq!(err.path, "users/{id}");
+    assert_eq!(err.existing_path, "users/{name}");
+    assert!(err.to_string().starts_with("The route path 'users/{id}' conflicts with the existing route path 'users/{name}' for GET requests"));
+    // Nothing was added:
+    assert_eq!(names(&api), vec!["users/list", "users/{name}"]);
+
+    // Merging the same routes twice conflicts too:
+    let mut api = Api::new();
+    api.merge(orders::api()).unwrap();
+    assert!(api.merge(orders::api()).is_err());
+}
+
+#[ApiBody(Deserialize)]
+struct ThingPath {
+    id: usize
+}
+
+#[tokio::test]
+async fn mounted_routes_keep_their_middleware() {
+    let mut inner = Api::new();
+    inner.wrap(middleware_fn(|req, next| Box::pin(async move {
+        let mut res = next.run(req).await?;
+        res.headers_mut().append("x-layers", "inner".parse().unwrap());
+        Ok(res)
+    })));
+    // Unknown parameters (like those in the prefix it's mounted at) are ignored:
+    inner.add("{id}").handler(|Path(p): Path<ThingPath>| ToJson(p.id));
+
+    let mut api = Api::new();
+    api.wrap(middleware_fn(|req, next| Box::pin(async move {
+        let mut res = next.run(req).await?;
+        res.headers_mut().append("x-layers", "outer".parse().unwrap());
+        Ok(res)
+    })));
+    api.add("plain").handler(|| ToJson(0));
+    api.mount("orgs/{org}/things", inner).unwrap();
+
+    let req = http::Request::get("/orgs/acme/things/2").body(Bytes::from_vec(vec![])).unwrap();
+    let res = api.handle(req).await.unwrap();
+    let layers: Vec<_> = res.headers().get_all("x-layers").iter().map(|h| h.to_str().unwrap()).collect();
+    assert_eq!(layers, vec!["inner", "outer"]);
+    assert_eq!(res.into_body(), b"2".to_vec());
+
+    // Parameters in the prefix are described along with those of the route:
+    let info = api.info();
+    let params: Vec<_> = info[0].path_params.iter().map(|p| (p.name.as_str(), p.ty.ty.clone())).collect();
+    assert_eq!(params, vec![("org", ApiBodyType::String), ("id", ApiBodyType::Number)]);
+}
@@ -0,0 +1,126 @@
+use pretty_assertions::{ assert_eq };
+use seamless::{
+    api::{ Api, ApiBody, ApiBodyInfo, ApiBodyType, PathParamInfo },
+    handler::{ param::Path, response::ToJson },
+};
+use serde_json::{ json };
+
+mod common;
+use common::{ get };
+
+fn s(s: &str) -> String {
+    s.to_owned()
+}
+
+#[ApiBody(Deserialize)]
+struct PostPath {
+    /// The user ID
+    user_id: usize,
+    post: String
+}
+
+fn posts_api() -> Api {
+    let mut api = Api::new_with_base_path("api");
+    api.add("users/{user_id}/posts/{post}")
+        .handler(|Path(p): Path<PostPath>| ToJson(json!({ "user_id": p.user_id, "post": p.post })));
+    api.add("users/{id}")
+        .handler(|Path(id): Path<usize>| ToJson(json!({ "id": id })));
+    api.add("users/me")
+        .handler(|| ToJson(json!("me")));
+    api.add("users/{id}/friends/{idx}")
+        .handler(|Path((id, idx)): Path<(usize, u8)>| ToJson(json!([id, idx])));
+    api.add("users/{id}/name")
+        .handler(|Path(name): Path<String>| ToJson(json!(name)));
+    api
+}
+
+#[tokio::test]
+async fn path_params_are_captured() {
+    let api = posts_api();
+    assert_eq!(get(&api, "/api/users/1/posts/hello").await, Ok(json!({ "user_id": 1, "post": "hello" })));
+    assert_eq!(get(&api, "/api/users/42").await, Ok(json!({ "id": 42 })));
+    assert_eq!(get(&api, "/api/users/42/friends/3").await, Ok(json!([42, 3])));
+    // Segments are percent decoded:
+    assert_eq!(get(&api, "/api/users/1/posts/hello%20there").await, Ok(json!({ "user_id": 1, "post": "hello there" })));
+    assert_eq!(get(&api, "/api/users/J%C3%BCrgen/name").await, Ok(json!("Jürgen")));
+}
+
+#[tokio::test]
+async fn static_segments_take_priority() {
+    let api = posts_api();
+    assert_eq!(get(&api, "/api/users/me").await, Ok(json!("me")));
+}
+
+#[tokio::test]
+async fn invalid_or_missing_params() {
+    let api = posts_api();
+    // Can't be deserialized into the expected types:
+    assert_eq!(get(&api, "/api/users/bob").await, Err(400));
+    assert_eq!(get(&api, "/api/users/1/friends/300").await, Err(400));
+    // Doesn't match any route:
+    assert_eq!(get(&api, "/api/users").await, Err(404));
+    assert_eq!(get(&api, "/api/users/").await, Err(404));
+    assert_eq!(get(&api, "/api/users/1/posts").await, Err(404));
+    assert_eq!(get(&api, "/api/users/1/posts/a/b").await, Err(404));
+}
+
+#[test]
+fn path_params_in_route_info() {
+    let info = posts_api().info();
+    let params: Vec<_> = info.iter()
+        .map(|r| (r.name.as_str(), r.path_params.clone()))
+        .collect();
+
+    let param = |name: &str, description: &str, ty: ApiBodyType| PathParamInfo {
+        name: s(name),
+        ty: ApiBodyInfo { description: s(description), ty }
+    };
+
+    assert_eq!(params, vec![
+        ("users/me", vec![]),
+        ("users/{id}", vec![
+            param("id", "", ApiBodyType::Number)
+        ]),
+        ("users/{id}/friends/{idx}", vec![
+            param("id", "", ApiBodyType::Number),
+            param("idx", "", ApiBodyType::Number)
+        ]),
+        ("users/{id}/name", vec![
+            param("id", "", ApiBodyType::String)
+        ]),
+        ("users/{user_id}/posts/{post}", vec![
+            param("user_id", "The user ID", ApiBodyType::Number),
+            param("post", "", ApiBodyType::String)
+        ]),
+    ]);
+}
+
+#[test]
+#[should_panic(expected = "The route path 'users/{name}' conflicts with the existing route path 'users/{id}'")]
+fn conflicting_paths_panic() {
+    let mut api = Api::new();
+    api.add("users/{id}").handler(|Path(id): Path<usize>| ToJson(id));
+    api.add("users/{name}").handler(|Path(name): Path<String>| ToJson(name));
+}
+
+#[test]
+fn paths_with_different_methods_dont_conflict() {
+    let mut api = Api::new();
+    api.add("users/{id}").handler(|Path(id): Path<usize>| ToJson(id));
+    api.add("users/{name}").handler(|Path(name): Path<String>, _body: seamless::handler::body::FromJson<String>| ToJson(name));
+    assert_eq!(api.info().len(), 2);
+}
+
+#[test]
+#[should_panic(expected = "Invalid route path 'users/{id}/{id}'")]
+fn repeated_params_panic() {
+    let mut api = Api::new();
+    api.add("users/{id}/{id}").handler(|| ToJson(1));
+}
+
+#[test]
+#[should_panic(expected = "Invalid route path 'users/id-{id}'")]
+fn partial_segment_params_panic() {
+    let mut api = Api::new();
+    api.add("users/id-{id}").handler(|| ToJson(1));
+}
@@ -0,0 +1,97 @@
+use pretty_assertions::{ assert_eq };
+use seamless::{
+    api::{ Api, ApiBody, ApiBodyType, ApiError, RouteError },
+    handler::{ param::{ Path, Query }, request::Bytes, response::ToJson },
+};
+use serde_json::{ json, Value };
+
+#[ApiBody(Deserialize)]
+struct Search {
+    /// The term to search for
+    term: String,
+    limit: Option<usize>,
+    tag: Option<Vec<String>>
+}
+
+fn search_api() -> Api {
+    let mut api = Api::new();
+    api.add("search")
+        .handler(|Query(q): Query<Search>| ToJson(json!({ "term": q.term, "limit": q.limit, "tag": q.tag })));
+    api.add("users/{id}/search")
+        .handler(|Path(id): Path<usize>, Query(q): Query<Search>| ToJson(json!([id, q.term])));
+    api
+}
+
+async fn get(api: &Api, path: &str) -> Result<Value, ApiError> {
+    let req = http::Request::get(path).body(Bytes::from_vec(vec![])).unwrap();
+    match api.handle(req).await {
+        Ok(res) => Ok(serde_json::from_slice(&res.into_body()).unwrap()),
+        Err(RouteError::Err(e)) => Err(e),
+        Err(_) => panic!("route not found: {}", path)
+    }
+}
+
+#[tokio::test]
+async fn query_string_is_deserialized() {
+    let api = search_api();
+    assert_eq!(
+        get(&api, "/search?term=hello+world&limit=10").await,
+        Ok(json!({ "term": "hello world", "limit": 10, "tag": null }))
+    );
+    assert_eq!(
+        get(&api, "/search?term=caf%C3%A9&unknown=1").await,
+        Ok(json!({ "term": "café", "limit": null, "tag": null }))
+    );
+    assert_eq!(get(&api, "/users/3/search?term=x").await, Ok(json!([3, "x"])));
+}
+
+#[tokio::test]
+async fn repeated_keys_are_collected() {
+    let api = search_api();
+    assert_eq!(
+        get(&api, "/search?tag=a&term=x&tag=b&tag=c").await,
+        Ok(json!({ "term": "x", "limit": null, "tag": ["a", "b", "c"] }))
+    );
+    assert_eq!(
+        get(&api, "/search?tag=a&term=x").await,
+        Ok(json!({ "term": "x", "limit": null, "tag": ["a"] }))
+    );
+}
+
+#[tokio::test]
+async fn invalid_query_strings_are_rejected() {
+    let api = search_api();
+
+    let err = get(&api, "/search?term=x&limit=lots").await.unwrap_err();
+    assert_eq!(err.code, 400);
+    assert!(err.external_message.contains("'limit'"), "{}", err.external_message);
+    assert_eq!(err.value, Some(json!({ "field": "limit" })));
+
+    let err = get(&api, "/search?limit=1").await.unwrap_err();
+    assert_eq!(err.code, 400);
+    assert_eq!(err.value, Some(json!({ "field": "term" })));
+
+    // Only sequences can be given several values:
+    let err = get(&api, "/search?term=a&term=b").await.unwrap_err();
+    assert_eq!(err.code, 400);
+    assert_eq!(err.value, Some(json!({ "field": "term" })));
+
+    let err = get(&api, "/search?term=%FF").await.unwrap_err();
+    assert_eq!(err.code, 400);
+}
+
+#[test]
+fn query_shape_is_in_route_info() {
+    let api = search_api();
+    let info = api.info();
+    let query = info[0].query_type.as_ref().expect("query type should be recorded");
+    match &query.ty {
+        ApiBodyType::Object { keys } => {
+            assert_eq!(keys["term"].description, "The term to search for");
+            assert!(matches!(keys["limit"].ty, ApiBodyType::Optional { .. }));
+            assert!(matches!(keys["tag"].ty, ApiBodyType::Optional { .. }));
+        },
+        other => panic!("expected an object, got {:?}", other)
+    }
+    assert_eq!(api.info_with_definitions().routes[0].query_type, Some(Search::api_body_info_with_definitions().0));
+}
@@ -0,0 +1,218 @@
+use pretty_assertions::{ assert_eq };
+use seamless::{
+    api::{ Api, RouteError },
+    handler::{ body::FromJson, param::Path, request::Bytes, response::ToJson },
+};
+use serde_json::{ json, Value };
+
+mod common;
+use common::{ get };
+
+async fn request(api: &Api, method: &str, path: &str) -> Result<Value, u16> {
+    let req = http::Request::builder()
+        .method(method)
+        .uri(path)
+        .header("content-type", "application/json")
+        .body(Bytes::from_vec(b"null".to_vec()))
+        .unwrap();
+    common::send(api, req).await
+}
+
+fn files_api() -> Api {
+    let mut api = Api::new();
+    api.add("files/{*path}")
+        .handler(|Path(path): Path<String>| ToJson(json!({ "catch_all": path })));
+    api.add("files/{name}/info")
+        .handler(|Path(name): Path<String>| ToJson(json!({ "info": name })));
+    api.add("files/readme/info")
+        .handler(|| ToJson(json!("readme info")));
+    api.add("files/{name}")
+        .handler(|Path(name): Path<String>| ToJson(json!({ "name": name })));
+    api
+}
+
+#[tokio::test]
+async fn catch_all_captures_the_rest_of_the_path() {
+    let api = files_api();
+    assert_eq!(get(&api, "/files/a/b/c.txt").await, Ok(json!({ "catch_all": "a/b/c.txt" })));
+    assert_eq!(get(&api, "/files/a%20b/c").await, Ok(json!({ "catch_all": "a b/c" })));
+    // The catch-all must capture something:
+    assert_eq!(get(&api, "/files").await, Err(404));
+    assert_eq!(get(&api, "/files/").await, Err(404));
+}
+
+#[tokio::test]
+async fn static_beats_param_beats_catch_all() {
+    let api = files_api();
+    assert_eq!(get(&api, "/files/readme/info").await, Ok(json!("readme info")));
+    assert_eq!(get(&api, "/files/other/info").await, Ok(json!({ "info": "other" })));
+    assert_eq!(get(&api, "/files/other").await, Ok(json!({ "name": "other" })));
+    // If a more specific branch doesn't lead to a match, we fall back to the next one:
+    assert_eq!(get(&api, "/files/readme/other").await, Ok(json!({ "catch_all": "readme/other" })));
+    assert_eq!(get(&api, "/files/readme").await, Ok(json!({ "name": "readme" })));
+}
+
+#[tokio::test]
+async fn methods_are_matched_independently() {
+    let mut api = Api::new();
+    api.add("things/{id}")
+        .handler(|Path(id): Path<usize>| ToJson(json!({ "get": id })));
+    api.add("things/{name}")
+        .handler(|Path(name): Path<String>, _body: FromJson<()>| ToJson(json!({ "post": name })));
+    api.add("things/special")
+        .handler(|| ToJson(json!("special")));
+
+    assert_eq!(request(&api, "GET", "/things/1").await, Ok(json!({ "get": 1 })));
+    assert_eq!(request(&api, "POST", "/things/1").await, Ok(json!({ "post": "1" })));
+    assert_eq!(request(&api, "GET", "/things/special").await, Ok(json!("special")));
+    // There is no POST route at `things/special`, so the param route is used:
+    assert_eq!(request(&api, "POST", "/things/special").await, Ok(json!({ "post": "special" })));
+    assert_eq!(request(&api, "PUT", "/things/1").await, Err(405));
+}
+
+#[tokio::test]
+async fn wrong_method_is_not_allowed() {
+    let mut api = Api::new();
+    api.add("things/{id}")
+        .handler(|Path(id): Path<usize>| ToJson(id));
+    api.add("things/{name}")
+        .handler(|Path(name): Path<String>, _body: FromJson<()>| ToJson(name));
+    api.add("things/new")
+        .handler(|_body: FromJson<()>| ToJson(0));
+    api.add("echo")
+        .handler(|FromJson(body): FromJson<String>| ToJson(body));
+
+    let req = |method: &str, path: &str| http::Request::builder()
+        .method(method)
+        .uri(path)
+        .body(Bytes::from_vec(vec![]))
+        .unwrap();
+
+    let err = api.handle(req("GET", "/echo")).await.unwrap_err();
+    let res = err.method_not_allowed_response().unwrap();
+    assert_eq!(res.status(), 405);
+    assert_eq!(res.headers()["allow"], "OPTIONS, POST");
+    match err {
+        RouteError::MethodNotAllowed { allowed, request } => {
+            assert_eq!(allowed, vec![http::Method::OPTIONS, http::Method::POST]);
+            assert_eq!(request.uri().path(), "/echo");
+        },
+        e => panic!("expected MethodNotAllowed, got {:?}", e)
+    }
+
+    // Every route matching the path contributes to the allowed methods:
+    let err = api.handle(req("DELETE", "/things/new")).await.unwrap_err();
+    assert_eq!(err.method_not_allowed_response().unwrap().headers()["allow"], "GET, HEAD, OPTIONS, POST");
+
+    // Paths that don't match anything are still not found:
+    let err = api.handle(req("GET", "/nope")).await.unwrap_err();
+    assert!(matches!(err, RouteError::NotFound(_)));
+    assert!(err.method_not_allowed_response().is_none());
+}
+
+#[tokio::test]
+async fn options_and_head_are_handled() {
+    let mut api = Api::new();
+    api.add("things/{id}")
+        .handler(|Path(id): Path<usize>| ToJson(json!({ "id": id })));
+    api.add("things/{name}")
+        .handler(|Path(name): Path<String>, _body: FromJson<()>| ToJson(name));
+    api.add("echo")
+        .handler(|FromJson(body): FromJson<String>| ToJson(body));
+
+    let req = |method: &str, path: &str| http::Request::builder()
+        .method(method)
+        .uri(path)
+        .body(Bytes::from_vec(vec![]))
+        .unwrap();
+
+    // OPTIONS lists the allowed methods for the path:
+    let res = api.handle(req("OPTIONS", "/things/1")).await.unwrap();
+    assert_eq!(res.status(), 204);
+    assert_eq!(res.headers()["allow"], "GET, HEAD, OPTIONS, POST");
+    let res = api.handle(req("OPTIONS", "/echo")).await.unwrap();
+    assert_eq!(res.headers()["allow"], "OPTIONS, POST");
+    assert!(matches!(api.handle(req("OPTIONS", "/nope")).await, Err(RouteError::NotFound(_))));
+
+    // HEAD runs the GET handler but drops the body:
+    let get_res = api.handle(req("GET", "/things/1")).await.unwrap();
+    let head_res = api.handle(req("HEAD", "/things/1")).await.unwrap();
+    assert_eq!(head_res.status(), get_res.status());
+    assert_eq!(head_res.headers()["content-type"], get_res.headers()["content-type"]);
+    assert_eq!(head_res.headers()["content-length"], get_res.body().len().to_string().as_str());
+    assert!(head_res.body().is_empty());
+
+    // HEAD isn't allowed where there's no GET route:
+    assert!(matches!(api.handle(req("HEAD", "/echo")).await, Err(RouteError::MethodNotAllowed { .. })));
+}
+
+#[tokio::test]
+async fn many_routes() {
+    let mut api = Api::new_with_base_path("/api");
+    for n in 0..2000 {
+        api.add(format!("service{}/items", n))
+            .handler(move || ToJson(json!(n)));
+        api.add(format!("service{}/items/{{id}}", n))
+            .handler(move |Path(id): Path<usize>| ToJson(json!([n, id])));
+    }
+    assert_eq!(api.info().len(), 4000);
+    assert_eq!(get(&api, "/api/service0/items").await, Ok(json!(0)));
+    assert_eq!(get(&api, "/api/service1999/items").await, Ok(json!(1999)));
+    assert_eq!(get(&api, "/api/service1234/items/5").await, Ok(json!([1234, 5])));
+    assert_eq!(get(&api, "/api/service2000/items").await, Err(404));
+}
+
+#[test]
+#[cfg_attr(debug_assertions, should_panic(expected = "The route path 'files/{*path}' conflicts with the existing route path 'files/{*path}'"))]
+fn adding_the_same_route_twice() {
+    let mut api = Api::new();
+    api.add("files/{*path}").description("first").handler(|| ToJson(1));
+    api.add("files/{*path}").description("second").handler(|| ToJson(2));
+    // In release builds, the route is replaced:
+    let info = api.info();
+    assert_eq!(info.len(), 1);
+    assert_eq!(info[0].description, "second");
+}
+
+#[test]
+fn try_handler_hands_back_conflicts() {
+    let mut api = Api::new();
+    let first_line = line!(); api.add("files/{*path}").description("first").try_handler(|| ToJson(1)).unwrap();
+    let second_line = line!(); let err = api.add("files/{*path}").description("second").try_handler(|| ToJson(2)).unwrap_err();
+
+    assert_eq!(err.method, http::Method::GET);
+    assert_eq!(err.path, "files/{*path}");
+    assert_eq!(err.existing_path, "files/{*path}");
+    assert_eq!((err.location.file(), err.location.line()), (file!(), second_line));
+    assert_eq!((err.existing_location.file(), err.existing_location.line()), (file!(), first_line));
+    assert!(err.to_string().contains(&format!("(added at {}:{}:", file!(), second_line)));
+
+    // The original route is left alone:
+    let info = api.info();
+    assert_eq!(info.len(), 1);
+    assert_eq!(info[0].description, "first");
+
+    // Conflicts in groups are found straight away too:
+    api.group("files", |files| {
+        let err = files.add("{*rest}").try_handler(|| ToJson(3)).unwrap_err();
+        assert_eq!(err.path, "files/{*rest}");
+        assert_eq!(err.existing_path, "files/{*path}");
+        files.add("{name}").try_handler(|| ToJson(4)).unwrap();
+    });
+    assert_eq!(api.info().len(), 2);
+}
+
+#[test]
+#[should_panic(expected = "The route path 'files/{*rest}' conflicts with the existing route path 'files/{*path}'")]
+fn conflicting_catch_alls_panic() {
+    let mut api = Api::new();
+    api.add("files/{*path}").handler(|| ToJson(1));
+    api.add("files/{*rest}").handler(|| ToJson(2));
+}
+
+#[test]
+#[should_panic(expected = "must be the last segment of the path")]
+fn catch_all_must_be_last() {
+    let mut api = Api::new();
+    api.add("files/{*path}/info").handler(|| ToJson(1));
+}
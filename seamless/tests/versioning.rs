@@ -0,0 +1,143 @@
+use pretty_assertions::{ assert_eq };
+use seamless::{
+    api::{ Api, VersionSelector, PathVersion, HeaderVersion, MediaTypeVersion },
+    handler::{ body::FromJson, request::Bytes, response::ToJson },
+};
+use serde_json::{ json, Value };
+
+mod common;
+
+async fn request(api: &Api, req: http::request::Builder) -> Result<Value, u16> {
+    let req = req.header("content-type", "application/json").body(Bytes::from_vec(b"null".to_vec())).unwrap();
+    common::send(api, req).await
+}
+
+fn get(path: &str) -> http::request::Builder {
+    http::Request::get(path)
+}
+
+fn versioned_api() -> Api {
+    let mut api = Api::new_with_base_path("api");
+    api.add("users")
+        .version("v1")
+        .handler(|| ToJson("v1 users"));
+    api.add("users")
+        .version("v2")
+        .handler(|| ToJson("v2 users"));
+    api.add("users/{id}")
+        .handler(|| ToJson("shared user"));
+    api.add("users/me")
+        .version("v2")
+        .handler(|| ToJson("v2 me"));
+    api.add("status")
+        .handler(|| ToJson("ok"));
+    api.add("status")
+        .version("v2")
+        .handler(|FromJson(()): FromJson<()>| ToJson("v2 status"));
+    api
+}
+
+#[tokio::test]
+async fn path_version() {
+    let mut api = versioned_api();
+    api.versioning(PathVersion);
+
+    assert_eq!(request(&api, get("/api/v1/users")).await, Ok(json!("v1 users")));
+    assert_eq!(request(&api, get("/api/v2/users")).await, Ok(json!("v2 users")));
+    // Routes without a version handle every version:
+    assert_eq!(request(&api, get("/api/v1/users/1")).await, Ok(json!("shared user")));
+    assert_eq!(request(&api, get("/api/v3/users/1")).await, Ok(json!("shared user")));
+    assert_eq!(request(&api, get("/api/status")).await, Ok(json!("ok")));
+    // Routes for the version asked for take priority:
+    assert_eq!(request(&api, get("/api/v2/users/me")).await, Ok(json!("v2 me")));
+    assert_eq!(request(&api, get("/api/v1/users/me")).await, Ok(json!("shared user")));
+    // Requests for no version only match routes without a version:
+    assert_eq!(request(&api, get("/api/users")).await, Err(404));
+    assert_eq!(request(&api, get("/api/v3/users")).await, Err(404));
+    // The methods of shared and versioned routes are both allowed:
+    assert_eq!(request(&api, http::Request::post("/api/v2/status")).await, Ok(json!("v2 status")));
+    assert_eq!(request(&api, http::Request::delete("/api/v2/status")).await, Err(405));
+    let req = http::Request::options("/api/v2/status").body(Bytes::from_vec(vec![])).unwrap();
+    assert_eq!(api.handle(req).await.unwrap().headers()["allow"], "GET, HEAD, OPTIONS, POST");
+}
+
+#[tokio::test]
+async fn header_version() {
+    let mut api = versioned_api();
+    api.versioning(HeaderVersion::default());
+
+    assert_eq!(request(&api, get("/api/users").header("accept-version", "v1")).await, Ok(json!("v1 users")));
+    assert_eq!(request(&api, get("/api/users").header("Accept-Version", " v2 ")).await, Ok(json!("v2 users")));
+    assert_eq!(request(&api, get("/api/users/me").header("accept-version", "v2")).await, Ok(json!("v2 me")));
+    assert_eq!(request(&api, get("/api/users")).await, Err(404));
+    // The version isn't expected in the path:
+    assert_eq!(request(&api, get("/api/v1/users")).await, Err(404));
+
+    api.versioning(HeaderVersion::new(http::HeaderName::from_static("x-api-version")));
+    assert_eq!(request(&api, get("/api/users").header("x-api-version", "v1")).await, Ok(json!("v1 users")));
+}
+
+#[tokio::test]
+async fn media_type_version() {
+    let mut api = versioned_api();
+    api.versioning(MediaTypeVersion::default());
+
+    let accept = |value: &str| get("/api/users").header("accept", value);
+    assert_eq!(request(&api, accept("application/json; version=v1")).await, Ok(json!("v1 users")));
+    assert_eq!(request(&api, accept("text/plain, application/json;charset=utf-8; Version=\"v2\"")).await, Ok(json!("v2 users")));
+    assert_eq!(request(&api, accept("application/json")).await, Err(404));
+}
+
+// Versions can be selected in any way we like:
+struct QueryVersion;
+
+impl VersionSelector for QueryVersion {
+    fn select<'a>(&self, _headers: &http::HeaderMap, path: &'a str) -> (Option<String>, &'a str) {
+        match path.split_once('@') {
+            Some((path, version)) => (Some(version.to_owned()), path),
+            None => (None, path)
+        }
+    }
+}
+
+#[tokio::test]
+async fn custom_version_selector() {
+    let mut api = versioned_api();
+    api.versioning(QueryVersion);
+    assert_eq!(request(&api, get("/api/users@v2")).await, Ok(json!("v2 users")));
+    assert_eq!(request(&api, get("/api/users/1@v1")).await, Ok(json!("shared user")));
+}
+
+#[test]
+fn info_can_be_filtered_by_version() {
+    let api = versioned_api();
+    let routes = |info: Vec<seamless::api::RouteInfo>| -> Vec<(String, String, Option<String>)> {
+        info.into_iter().map(|r| (r.method, r.name, r.version)).collect()
+    };
+    let v = |s: &str| Some(s.to_owned());
+    let r = |method: &str, name: &str, version: Option<String>| (method.to_owned(), name.to_owned(), version);
+
+    assert_eq!(routes(api.info_for_version("v1")), vec![
+        r("GET", "status", None),
+        r("GET", "users", v("v1")),
+        r("GET", "users/{id}", None),
+    ]);
+    assert_eq!(routes(api.info_for_version("v2")), vec![
+        r("GET", "status", None),
+        r("POST", "status", v("v2")),
+        r("GET", "users", v("v2")),
+        r("GET", "users/me", v("v2")),
+        r("GET", "users/{id}", None),
+    ]);
+    assert_eq!(api.info().len(), 6);
+    assert_eq!(api.info_with_definitions_for_version("v1").routes.len(), 3);
+}
+
+#[test]
+#[should_panic(expected = "The route path 'users' conflicts with the existing route path 'users'")]
+fn routes_conflict_within_a_version() {
+    let mut api = Api::new();
+    api.add("users").version("v1").handler(|| ToJson(1));
+    api.add("users").handler(|| ToJson(2));
+    api.add("users").version("v1").handler(|| ToJson(3));
+}